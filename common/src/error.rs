@@ -0,0 +1,117 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+use computer::Fault;
+
+/// The ways solving one of the days can fail. Each of the day binaries return this from `main()`
+/// so a failure is reported the same way no matter which puzzle it came from.
+pub enum AocError {
+    /// The puzzle input couldn't be read.
+    Io(io::Error),
+
+    /// An IntCode program faulted while it was running.
+    Fault(Fault),
+
+    /// The puzzle input wasn't in the expected format.
+    Parse(String),
+
+    /// Everything ran but the result doesn't make sense, such as a diagnostic program reporting
+    /// failing tests.
+    InvalidOutput(String),
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "unable to read the input: {}", err),
+            Self::Fault(fault) => write!(f, "program faulted: {}", fault),
+            Self::Parse(msg) => write!(f, "invalid input: {}", msg),
+            Self::InvalidOutput(msg) => write!(f, "unexpected output: {}", msg),
+        }
+    }
+}
+
+/// Returning an error from `main()` prints it using its `Debug` implementation. The derived one
+/// isn't very friendly to read so this uses the `Display` version instead.
+impl fmt::Debug for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Fault(fault) => Some(fault),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<Fault> for AocError {
+    fn from(fault: Fault) -> Self {
+        Self::Fault(fault)
+    }
+}
+
+impl From<ParseIntError> for AocError {
+    fn from(err: ParseIntError) -> Self {
+        Self::Parse(err.to_string())
+    }
+}
+
+impl From<String> for AocError {
+    fn from(msg: String) -> Self {
+        Self::Parse(msg)
+    }
+}
+
+impl From<&str> for AocError {
+    fn from(msg: &str) -> Self {
+        Self::Parse(msg.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        let io_err = AocError::from(io::Error::new(io::ErrorKind::NotFound, "missing file"));
+        assert_eq!(io_err.to_string(), "unable to read the input: missing file");
+        assert!(io_err.source().is_some());
+
+        let fault_err = AocError::from(Fault::MemoryExceeded);
+        assert_eq!(
+            fault_err.to_string(),
+            "program faulted: attempted to access memory beyond its end"
+        );
+        assert!(fault_err.source().is_some());
+
+        let parse_err = AocError::from("abc".parse::<usize>().unwrap_err());
+        assert_eq!(
+            parse_err.to_string(),
+            "invalid input: invalid digit found in string"
+        );
+        assert_eq!(
+            AocError::from("bad direction").to_string(),
+            "invalid input: bad direction"
+        );
+
+        let output_err = AocError::InvalidOutput("tests failed".to_string());
+        assert_eq!(output_err.to_string(), "unexpected output: tests failed");
+
+        // Debug is used when returned from main so should read the same
+        assert_eq!(format!("{:?}", output_err), output_err.to_string());
+    }
+}
@@ -0,0 +1,199 @@
+use std::cmp;
+
+/// A location on an unbounded two dimensional grid. Following day 3's convention `y` increases
+/// going up and `x` increases going right.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// The largest of the differences along either axis between this point and another, which is
+    /// the number of moves it would take if diagonal moves were allowed.
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        let dx = (self.x - other.x).unsigned_abs();
+        let dy = (self.y - other.y).unsigned_abs();
+
+        cmp::max(dx, dy)
+    }
+
+    /// The straight line distance between this point and another, ignoring the grid entirely.
+    pub fn euclidean_distance(&self, other: &Self) -> f64 {
+        let dx = (self.x - other.x) as f64;
+        let dy = (self.y - other.y) as f64;
+
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Calculates the absolute sum of differences between this point and another provided one.
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    /// The four points sharing an edge with this one, for puzzles where movement is only allowed
+    /// along the grid's axes. These are always returned clockwise starting with the point above:
+    /// up, right, down, left.
+    pub fn neighbors4(&self) -> [Point; 4] {
+        [
+            Self::new(self.x, self.y + 1),
+            Self::new(self.x + 1, self.y),
+            Self::new(self.x, self.y - 1),
+            Self::new(self.x - 1, self.y),
+        ]
+    }
+
+    /// All eight points surrounding this one including the diagonals, which is what Conway style
+    /// cellular automata count. These are also returned clockwise starting with the point above,
+    /// so the four axis neighbors end up at the even indexes in the same order as `neighbors4()`.
+    pub fn neighbors8(&self) -> [Point; 8] {
+        [
+            Self::new(self.x, self.y + 1),
+            Self::new(self.x + 1, self.y + 1),
+            Self::new(self.x + 1, self.y),
+            Self::new(self.x + 1, self.y - 1),
+            Self::new(self.x, self.y - 1),
+            Self::new(self.x - 1, self.y - 1),
+            Self::new(self.x - 1, self.y),
+            Self::new(self.x - 1, self.y + 1),
+        ]
+    }
+}
+
+/// Which way the path through three points bends at the middle one, or whether it doesn't bend at
+/// all because they all lie on the same line.
+#[derive(Debug, PartialEq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    Colinear,
+}
+
+impl Orientation {
+    /// This caculates the three point orientation of any three points so we can determine the
+    /// relation between the points for the edge and general cases of segment intersection. This is
+    /// calculated using the slope between p1/p2, and p2/p3. If the slope is the same
+    /// (difference of zero) the two lines are colinear. If the slope of p1/p2 is less than p2/p3
+    /// than the p2/p3 slope is bending counterclockwise from the p1/p2 slope, when it's more it's
+    /// bending more clockwise from the slope.
+    ///
+    /// Anything that can become a point is accepted, so types carrying extra information along
+    /// with their coordinates can be passed in directly.
+    pub fn from_three_points(
+        p1: impl Into<Point>,
+        p2: impl Into<Point>,
+        p3: impl Into<Point>,
+    ) -> Self {
+        let (p1, p2, p3) = (p1.into(), p2.into(), p3.into());
+        let orientation = (p2.y - p1.y) * (p3.x - p2.x) - (p2.x - p1.x) * (p3.y - p2.y);
+
+        match orientation {
+            orient if orient < 0 => Self::CounterClockwise,
+            orient if orient > 0 => Self::Clockwise,
+            _ => Self::Colinear,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_manhattan_distance() {
+        let origin = Point::new(0, 0);
+
+        assert_eq!(origin.manhattan_distance(&Point::new(3, 0)), 3);
+        assert_eq!(origin.manhattan_distance(&Point::new(-6, -6)), 12);
+        assert_eq!(Point::new(-3, 6).manhattan_distance(&origin), 9);
+    }
+
+    #[test]
+    fn test_chebyshev_distance() {
+        let origin = Point::new(0, 0);
+
+        assert_eq!(origin.chebyshev_distance(&Point::new(3, 0)), 3);
+        assert_eq!(origin.chebyshev_distance(&Point::new(-6, -6)), 6);
+        assert_eq!(Point::new(-3, 6).chebyshev_distance(&origin), 6);
+    }
+
+    #[test]
+    fn test_euclidean_distance() {
+        let origin = Point::new(0, 0);
+
+        assert!((origin.euclidean_distance(&Point::new(3, -4)) - 5.0).abs() < f64::EPSILON);
+        assert!(
+            (origin.euclidean_distance(&Point::new(-6, -6)) - 72f64.sqrt()).abs() < f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn test_orientation() {
+        let origin = Point::new(0, 0);
+        let up = Point::new(0, 5);
+
+        assert_eq!(
+            Orientation::from_three_points(origin, up, Point::new(0, 10)),
+            Orientation::Colinear
+        );
+        assert_eq!(
+            Orientation::from_three_points(origin, up, Point::new(5, 10)),
+            Orientation::Clockwise
+        );
+        assert_eq!(
+            Orientation::from_three_points(origin, up, Point::new(-5, 10)),
+            Orientation::CounterClockwise
+        );
+    }
+
+    #[test]
+    fn test_neighbors4() {
+        let origin = Point::new(0, 0);
+
+        assert_eq!(
+            origin.neighbors4(),
+            [
+                Point::new(0, 1),
+                Point::new(1, 0),
+                Point::new(0, -1),
+                Point::new(-1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_neighbors8() {
+        let origin = Point::new(0, 0);
+        let neighbors = origin.neighbors8();
+
+        assert_eq!(
+            neighbors,
+            [
+                Point::new(0, 1),
+                Point::new(1, 1),
+                Point::new(1, 0),
+                Point::new(1, -1),
+                Point::new(0, -1),
+                Point::new(-1, -1),
+                Point::new(-1, 0),
+                Point::new(-1, 1),
+            ]
+        );
+
+        // Every point of the surrounding 3x3 square other than the origin itself, exactly once
+        let unique: HashSet<Point> = neighbors.iter().cloned().collect();
+        assert_eq!(unique.len(), 8);
+        assert!(!unique.contains(&origin));
+        assert!(neighbors.iter().all(|p| p.x.abs() <= 1 && p.y.abs() <= 1));
+
+        // The axis neighbors sit at the even positions in the same order as neighbors4
+        let axis: Vec<Point> = neighbors.iter().step_by(2).cloned().collect();
+        assert_eq!(axis, origin.neighbors4().to_vec());
+    }
+}
@@ -0,0 +1,7 @@
+//! Pieces shared between the individual days that don't belong to any one puzzle.
+
+pub mod error;
+pub mod geometry;
+
+pub use error::AocError;
+pub use geometry::{Orientation, Point};
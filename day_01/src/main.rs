@@ -1,6 +1,8 @@
 use std::fs::File;
 use std::io::Read;
 
+use common::AocError;
+
 pub fn calculate_fuel(mass: usize) -> usize {
     let fuel_requirement = mass / 3;
 
@@ -31,22 +33,93 @@ pub fn recursive_fuel_cost(mass: usize) -> usize {
     total_fuel_mass
 }
 
-fn main() {
-    let mut in_dat_fh = File::open("./data/input_01.txt").unwrap();
-    let mut in_dat = String::new();
+/// Computes the same total as `recursive_fuel_cost()` without repeatedly applying the fuel
+/// calculation. Dividing by three and subtracting two is the same as `(mass + 3) / 3 - 3`, so
+/// after shifting everything up by three each round is just another division by three:
+///
+/// fuel_k = (mass + 3) / 3^k - 3
+///
+/// The total is the sum of those terms while they're positive. The sum of `n / 3^k` over every `k`
+/// has a closed form in terms of the base three digits of `n` (Legendre's formula), so the terms
+/// that don't get included can be subtracted off rather than adding up the ones that do. Summing
+/// the digits is still a small loop, there isn't any avoiding that one.
+pub fn recursive_fuel_closed_form(mass: usize) -> usize {
+    let shifted = mass + 3;
+
+    // The first term is only positive once the shifted mass divided by three is at least 4
+    if shifted < 12 {
+        return 0;
+    }
+
+    // The number of positive terms, each of those has a floored division of at least 4
+    let term_count = (shifted / 4).ilog(3);
+
+    let all_divisions = (shifted - base3_digit_sum(shifted)) / 2;
+
+    // Everything after the last positive term, the first of these is always between 1 and 3
+    let remainder = shifted / 3usize.pow(term_count + 1);
+    let excluded = remainder + (remainder - base3_digit_sum(remainder)) / 2;
+
+    all_divisions - excluded - 3 * term_count as usize
+}
+
+fn base3_digit_sum(num: usize) -> usize {
+    let mut remaining = num;
+    let mut sum = 0;
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
+    while remaining > 0 {
+        sum += remaining % 3;
+        remaining /= 3;
+    }
+
+    sum
+}
 
-    let input_masses: Vec<usize> = in_dat
+/// The total fuel needed for every one of the provided module masses. When `recursive` is set the
+/// fuel needed to carry the fuel is included as well (part two).
+pub fn total_fuel(masses: &[usize], recursive: bool) -> usize {
+    masses
+        .iter()
+        .map(|mass| {
+            if recursive {
+                recursive_fuel_cost(*mass)
+            } else {
+                calculate_fuel(*mass)
+            }
+        })
+        .sum()
+}
+
+/// Parses the module masses from the puzzle input, one per line. Blank lines are skipped, anything
+/// else that isn't a number is reported along with the line (counting from one) it was found on.
+pub fn parse_masses(input: &str) -> Result<Vec<usize>, String> {
+    input
         .lines()
-        .map(|i| i.parse::<usize>().unwrap())
-        .collect();
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            line.trim()
+                .parse::<usize>()
+                .map_err(|err| format!("line {} (`{}`) isn't a valid mass: {}", idx + 1, line, err))
+        })
+        .collect()
+}
+
+fn main() -> Result<(), AocError> {
+    let mut in_dat_fh = File::open("./data/input_01.txt")?;
+    let mut in_dat = String::new();
+
+    in_dat_fh.read_to_string(&mut in_dat)?;
+
+    let input_masses = parse_masses(&in_dat)?;
 
-    let first_result: usize = input_masses.iter().map(|i| calculate_fuel(*i)).sum();
+    let first_result = total_fuel(&input_masses, false);
     println!("Fuel required: {}", first_result);
 
-    let second_result: usize = input_masses.iter().map(|i| recursive_fuel_cost(*i)).sum();
+    let second_result = total_fuel(&input_masses, true);
     println!("Recursive fuel calculation: {}", second_result);
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -67,4 +140,42 @@ mod test {
         assert_eq!(recursive_fuel_cost(1969), 966);
         assert_eq!(recursive_fuel_cost(100756), 50346);
     }
+
+    #[test]
+    fn test_closed_form_fuel_calculations() {
+        assert_eq!(recursive_fuel_closed_form(12), 2);
+        assert_eq!(recursive_fuel_closed_form(1969), 966);
+        assert_eq!(recursive_fuel_closed_form(100756), 50346);
+
+        for mass in 0..200_000 {
+            assert_eq!(
+                recursive_fuel_closed_form(mass),
+                recursive_fuel_cost(mass),
+                "mass {}",
+                mass
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_masses() {
+        assert_eq!(parse_masses("12\n14\n1969\n"), Ok(vec![12, 14, 1969]));
+        assert_eq!(parse_masses("12\n\n  \n100756\n\n"), Ok(vec![12, 100756]));
+        assert_eq!(parse_masses(""), Ok(vec![]));
+
+        let err = parse_masses("12\n\nfuel\n14").unwrap_err();
+        assert!(err.starts_with("line 3 (`fuel`)"), "{}", err);
+
+        let err = parse_masses("12\n-14").unwrap_err();
+        assert!(err.starts_with("line 2 (`-14`)"), "{}", err);
+    }
+
+    #[test]
+    fn test_total_fuel() {
+        let masses = [12, 14, 1969, 100756];
+
+        assert_eq!(total_fuel(&masses, false), 2 + 2 + 654 + 33583);
+        assert_eq!(total_fuel(&masses, true), 2 + 2 + 966 + 50346);
+        assert_eq!(total_fuel(&[], true), 0);
+    }
 }
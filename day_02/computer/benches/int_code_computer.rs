@@ -0,0 +1,111 @@
+//! Baseline performance numbers for the IntCodeComputer. These can be run from the `day_02`
+//! directory with `cargo bench -p computer`, criterion will report the throughput in executed
+//! instructions per second and compare against the previous run if there was one.
+
+use std::str::FromStr;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use computer::{Fault, IntCodeComputer};
+
+/// A synthetic program that counts down from 10,000 adding each value of the counter into an
+/// accumulator before outputting the result. This is almost entirely arithmetic and jumps which
+/// is what the later puzzle programs spend the majority of their time doing.
+const COUNTDOWN_PROG: &str = "1001,14,-1,14,1,14,15,15,1005,14,0,4,15,99,10000,0";
+
+/// The first feedback loop sample program from day 7.
+const FEEDBACK_PROG: &str =
+    "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+
+/// Mirrors `IntCodeComputer::run()` but keeps track of how many steps were taken so we can report
+/// the throughput of the interpreter rather than just the time taken.
+fn run_counted(icc: &mut IntCodeComputer) -> Result<u64, Fault> {
+    let mut steps = 0;
+
+    loop {
+        icc.step()?;
+        steps += 1;
+
+        if icc.is_halted() || icc.is_waiting_on_input() {
+            return Ok(steps);
+        }
+    }
+}
+
+fn countdown() -> u64 {
+    let mut icc = IntCodeComputer::from_str(COUNTDOWN_PROG).unwrap();
+    let steps = run_counted(&mut icc).unwrap();
+    assert_eq!(icc.output(), vec![49_995_000]);
+
+    steps
+}
+
+/// Runs the feedback loop for a single set of phase settings returning the final signal and the
+/// total number of steps executed across all of the amplifiers.
+fn feedback_chain(settings: &[isize]) -> (isize, u64) {
+    let mut computers: Vec<IntCodeComputer> = settings
+        .iter()
+        .map(|phase| {
+            let mut icc = IntCodeComputer::from_str(FEEDBACK_PROG).unwrap();
+            icc.add_input(vec![*phase]);
+            icc
+        })
+        .collect();
+
+    let mut signal = 0;
+    let mut steps = 0;
+
+    while !computers[computers.len() - 1].is_halted() {
+        for icc in computers.iter_mut() {
+            icc.add_input(vec![signal]);
+            steps += run_counted(icc).unwrap();
+            signal = icc.output()[0];
+        }
+    }
+
+    (signal, steps)
+}
+
+fn feedback_search() -> u64 {
+    let mut max_signal = 0;
+    let mut steps = 0;
+
+    for a in 5..10 {
+        for b in 5..10 {
+            for c in 5..10 {
+                for d in 5..10 {
+                    for e in 5..10 {
+                        let settings = [a, b, c, d, e];
+                        if (5..10).any(|phase| !settings.contains(&phase)) {
+                            continue;
+                        }
+
+                        let (signal, chain_steps) = feedback_chain(&settings);
+                        max_signal = max_signal.max(signal);
+                        steps += chain_steps;
+                    }
+                }
+            }
+        }
+    }
+
+    assert_eq!(max_signal, 139_629_729);
+    steps
+}
+
+fn bench_countdown(c: &mut Criterion) {
+    let mut group = c.benchmark_group("countdown");
+    group.throughput(Throughput::Elements(countdown()));
+    group.bench_function("arithmetic_and_jumps", |b| b.iter(countdown));
+    group.finish();
+}
+
+fn bench_feedback_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("feedback_search");
+    group.throughput(Throughput::Elements(feedback_search()));
+    group.bench_function("day_07_phase_permutations", |b| b.iter(feedback_search));
+    group.finish();
+}
+
+criterion_group!(benches, bench_countdown, bench_feedback_search);
+criterion_main!(benches);
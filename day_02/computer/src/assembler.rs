@@ -0,0 +1,237 @@
+//! Writing IntCode by hand means working out the parameter mode digits of every instruction,
+//! which gets old fast. This turns the same text `disassemble()` produces back into a program.
+
+use crate::int_code_computer::{Operation, EXTENSION_HALT_WITH_CODE};
+
+/// Looks up the opcode for a mnemonic, along with the operation it decodes to so the number and
+/// kind of parameters it takes can be checked. The parameter modes are filled in separately.
+fn lookup(mnemonic: &str) -> Option<(isize, Operation)> {
+    let found = match mnemonic {
+        "ADD" => (1, Operation::Add(0)),
+        "MUL" => (2, Operation::Mul(0)),
+        "IN" => (3, Operation::Input(0)),
+        "OUT" => (4, Operation::Output(0)),
+        "JT" => (5, Operation::JumpIfTrue(0)),
+        "JF" => (6, Operation::JumpIfFalse(0)),
+        "LT" => (7, Operation::LessThan(0)),
+        "EQ" => (8, Operation::Equals(0)),
+        "ARB" => (9, Operation::AdjustRelativeBase(0)),
+        "HALT" => (99, Operation::Halt),
+        "EXIT" => (EXTENSION_HALT_WITH_CODE, Operation::HaltWithCode(0)),
+        _ => return None,
+    };
+
+    Some(found)
+}
+
+/// Parses a single parameter into its mode and raw value. Parameters use the same notation as the
+/// disassembler: `[5]` for position mode, `#5` for immediate mode, and `[rb+5]` or `[rb-5]` for
+/// relative mode.
+fn parse_parameter(param: &str) -> Result<(isize, isize), String> {
+    let parse_value = |raw: &str| {
+        raw.parse::<isize>()
+            .map_err(|_| format!("parameter `{}` doesn't have a valid value", param))
+    };
+
+    if let Some(raw) = param.strip_prefix('#') {
+        return Ok((1, parse_value(raw)?));
+    }
+
+    match param.strip_prefix('[').and_then(|p| p.strip_suffix(']')) {
+        Some(inner) => match inner.strip_prefix("rb") {
+            Some(offset) => Ok((2, parse_value(offset.trim_start_matches('+'))?)),
+            None => Ok((0, parse_value(inner)?)),
+        },
+        None => Err(format!("parameter `{}` isn't in a known mode", param)),
+    }
+}
+
+/// Assembles a single line into the values it represents in the program.
+fn assemble_line(line: &str) -> Result<Vec<isize>, String> {
+    let mut tokens: Vec<&str> = line.split_whitespace().collect();
+
+    // Addresses are purely informational, they're only accepted so disassembled programs can be
+    // fed straight back in
+    if tokens.first().is_some_and(|t| t.ends_with(':')) {
+        tokens.remove(0);
+    }
+
+    let (mnemonic, params) = match tokens.split_first() {
+        Some(split) => split,
+        None => return Ok(Vec::new()),
+    };
+
+    if *mnemonic == "DATA" {
+        return params
+            .iter()
+            .map(|val| {
+                val.parse::<isize>()
+                    .map_err(|_| format!("data value `{}` isn't a number", val))
+            })
+            .collect();
+    }
+
+    let (opcode, op) =
+        lookup(mnemonic).ok_or_else(|| format!("unknown mnemonic `{}`", mnemonic))?;
+
+    // The destination of operations that write to memory is marked with an arrow, which has to
+    // be on the last parameter and can't be anywhere else
+    let mut params = params.to_vec();
+    let has_arrow = params.len() >= 2 && params[params.len() - 2] == "->";
+    if has_arrow {
+        params.remove(params.len() - 2);
+    }
+
+    if params.contains(&"->") || (has_arrow && !op.writes_memory()) {
+        return Err(format!(
+            "{} doesn't write anywhere for `->` to point at",
+            mnemonic
+        ));
+    }
+
+    if params.len() != op.arity() {
+        return Err(format!(
+            "{} takes {} parameters but was given {}",
+            mnemonic,
+            op.arity(),
+            params.len()
+        ));
+    }
+
+    let mut instruction = vec![opcode];
+    let mut multiplier = 100;
+
+    for (idx, param) in params.iter().enumerate() {
+        let (mode, value) = parse_parameter(param)?;
+
+        if mode == 1 && op.writes_memory() && idx == params.len() - 1 {
+            return Err(format!(
+                "{} can't write to immediate value {}",
+                mnemonic, param
+            ));
+        }
+
+        instruction[0] += mode * multiplier;
+        instruction.push(value);
+        multiplier *= 10;
+    }
+
+    Ok(instruction)
+}
+
+/// Assembles a program from source text into the comma separated format `from_str()` expects.
+/// Each line holds a single instruction in the same format that `disassemble()` produces, such as
+/// `ADD #1 [5] -> [6]`, and the leading address on each line is optional. Raw values can be
+/// included with `DATA`, which takes any number of them. Blank lines are ignored, as is anything
+/// following a `;` so programs can be commented.
+///
+/// Errors describe the problem along with the line (counting from one) it was found on.
+pub fn assemble(source: &str) -> Result<String, String> {
+    let mut program = Vec::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let code = line.split(';').next().unwrap_or("");
+        let values = assemble_line(code).map_err(|err| format!("line {}: {}", idx + 1, err))?;
+
+        program.extend(values);
+    }
+
+    Ok(program
+        .iter()
+        .map(|val| val.to_string())
+        .collect::<Vec<String>>()
+        .join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::IntCodeComputer;
+
+    #[test]
+    fn test_assembling_instructions() {
+        assert_eq!(assemble("ADD #1 [5] -> [6]"), Ok("101,1,5,6".to_string()));
+        assert_eq!(assemble("MUL [4] #3 -> [4]"), Ok("1002,4,3,4".to_string()));
+        assert_eq!(assemble("IN -> [rb-2]"), Ok("203,-2".to_string()));
+        assert_eq!(assemble("JT #1 [rb+7]"), Ok("2105,1,7".to_string()));
+        assert_eq!(
+            assemble("LT #1 #2 -> [rb+0]"),
+            Ok("21107,1,2,0".to_string())
+        );
+        assert_eq!(assemble("HALT"), Ok("99".to_string()));
+        assert_eq!(assemble("DATA 1 -2 3"), Ok("1,-2,3".to_string()));
+    }
+
+    #[test]
+    fn test_assembly_errors() {
+        assert!(assemble("NOP")
+            .unwrap_err()
+            .starts_with("line 1: unknown mnemonic"));
+        assert!(assemble("HALT\nADD #1 #2")
+            .unwrap_err()
+            .starts_with("line 2: ADD takes 3 parameters"));
+
+        // Writes can't go to an immediate value, and only writes get an arrow
+        assert!(assemble("ADD #1 #2 -> #3").is_err());
+        assert!(assemble("OUT -> [3]").is_err());
+        assert!(assemble("ADD #1 -> #2 [3]").is_err());
+
+        assert!(assemble("OUT [x]").is_err());
+        assert!(assemble("OUT 5").is_err());
+        assert!(assemble("DATA 5 y").is_err());
+    }
+
+    #[test]
+    fn test_assembled_program() {
+        let source = "
+            ; Doubles whatever it is given, a zero ends the program
+            IN -> [16]
+            EQ [16] #0 -> [17]
+            JT [17] #15
+            MUL [16] #2 -> [16]
+            OUT [16]
+
+            HALT ; Reached through the jump above
+            DATA 0 0
+        ";
+
+        let program = assemble(source).unwrap();
+        assert_eq!(
+            program,
+            "3,16,1008,16,0,17,1005,17,15,1002,16,2,16,4,16,99,0,0"
+        );
+
+        let mut icc = IntCodeComputer::from_str(&program).unwrap();
+        icc.add_input(vec![21]);
+        icc.run().unwrap();
+        assert_eq!(icc.output(), vec![42]);
+
+        let mut icc = IntCodeComputer::from_str(&program).unwrap();
+        icc.add_input(vec![0]);
+        icc.run().unwrap();
+        assert!(icc.is_halted());
+        assert_eq!(icc.output(), vec![]);
+    }
+
+    #[test]
+    fn test_disassembly_round_trip() {
+        let program = "109,-3,21101,6,7,13,3,9,204,2,99";
+        let icc = IntCodeComputer::from_str(program).unwrap();
+
+        assert_eq!(
+            assemble(&icc.disassemble().join("\n")),
+            Ok(program.to_string())
+        );
+
+        // Halting with a code is an extension, so it's only disassembled when they're allowed
+        let program = "1101,2,3,5,97,7";
+        let mut icc = IntCodeComputer::from_str(program).unwrap();
+        icc.set_allow_extensions(true);
+
+        let source = icc.disassemble().join("\n");
+        assert!(source.contains("EXIT [7]"));
+        assert_eq!(assemble(&source), Ok(program.to_string()));
+    }
+}
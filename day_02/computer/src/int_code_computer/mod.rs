@@ -1,38 +1,290 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead};
 use std::str::FromStr;
 
-/// The amount of RAM the IntCodeComputer has. I could change the implementation to allow for
-/// arbitrary sized inputs by using a Vec<_> instead, but this feels more appropriate for the task.
+use log::{debug, log_enabled, Level};
+
+/// The amount of RAM the IntCodeComputer starts out with. Day 9 programs expect to be able to
+/// use memory well beyond the end of the program, so memory grows on demand past this whenever
+/// something is stored beyond it.
 pub const MEMORY_SIZE: usize = 1024;
 
+/// The furthest memory is allowed to grow. Nothing legitimate comes anywhere near this, but a
+/// broken program storing to some enormous address shouldn't take the whole host down with it.
+pub const MEMORY_LIMIT: usize = 1 << 20;
+
+/// The opcode used by the non-standard halt with exit code extension. This was chosen as it
+/// doesn't conflict with any of the official operations.
+pub const EXTENSION_HALT_WITH_CODE: isize = 97;
+
 /// This error state encapsulates the various ways a program run on the IntCodeComputer can fail
 /// and would generally be considered a hardware fault if it happened on a real machine.
 #[derive(Debug, PartialEq)]
 pub enum Fault {
+    EmptyJournal,
     InvalidProgramCount(usize, isize),
+    InvalidToken(usize, String),
+    IoError(String),
     MemoryExceeded,
-    MissingMemory(usize, usize),
+    MisalignedJump(usize),
+    OutputOverflow,
     NegativeMemoryAddress(usize, isize),
     ParameterModeInvalid(usize),
     ProgramTooBig(usize),
+    StepLimitExceeded(usize),
     UninitializedOperation(usize),
     UnknownOperation(usize, isize),
 }
 
+impl Fault {
+    /// A stable machine readable form of the fault, made up of the variant name followed by each
+    /// of its values separated by colons such as `UnknownOperation:10:7500`. This is intended for
+    /// saving reports and test fixtures, `from_code()` will turn it back into the same fault.
+    pub fn to_code(&self) -> String {
+        match self {
+            Self::EmptyJournal => "EmptyJournal".to_string(),
+            Self::InvalidProgramCount(pc, target) => {
+                format!("InvalidProgramCount:{}:{}", pc, target)
+            }
+            Self::InvalidToken(index, token) => format!("InvalidToken:{}:{}", index, token),
+            Self::IoError(msg) => format!("IoError:{}", msg),
+            Self::MemoryExceeded => "MemoryExceeded".to_string(),
+            Self::MisalignedJump(target) => format!("MisalignedJump:{}", target),
+            Self::OutputOverflow => "OutputOverflow".to_string(),
+            Self::NegativeMemoryAddress(pc, address) => {
+                format!("NegativeMemoryAddress:{}:{}", pc, address)
+            }
+            Self::ParameterModeInvalid(address) => format!("ParameterModeInvalid:{}", address),
+            Self::ProgramTooBig(size) => format!("ProgramTooBig:{}", size),
+            Self::StepLimitExceeded(limit) => format!("StepLimitExceeded:{}", limit),
+            Self::UninitializedOperation(address) => format!("UninitializedOperation:{}", address),
+            Self::UnknownOperation(address, op) => format!("UnknownOperation:{}:{}", address, op),
+        }
+    }
+
+    /// Parses the output of `to_code()` back into a fault. Returns None if the name isn't a known
+    /// fault or it doesn't have the right values for that fault. The message of an `IoError` is
+    /// everything following the name, and the token of an `InvalidToken` everything following its
+    /// index, so they are free to contain colons themselves.
+    pub fn from_code(code: &str) -> Option<Fault> {
+        let mut parts = code.splitn(2, ':');
+        let name = parts.next()?;
+        let payload = parts.next();
+
+        let fields: Vec<&str> = match payload {
+            Some(p) => p.split(':').collect(),
+            None => Vec::new(),
+        };
+
+        let fault = match (name, fields.as_slice()) {
+            ("EmptyJournal", []) => Self::EmptyJournal,
+            ("InvalidProgramCount", [pc, target]) => {
+                Self::InvalidProgramCount(pc.parse().ok()?, target.parse().ok()?)
+            }
+            ("InvalidToken", [_, _, ..]) => {
+                let mut token_parts = payload?.splitn(2, ':');
+                let index = token_parts.next()?.parse().ok()?;
+                Self::InvalidToken(index, token_parts.next()?.to_string())
+            }
+            ("IoError", _) => Self::IoError(payload?.to_string()),
+            ("MemoryExceeded", []) => Self::MemoryExceeded,
+            ("MisalignedJump", [target]) => Self::MisalignedJump(target.parse().ok()?),
+            ("OutputOverflow", []) => Self::OutputOverflow,
+            ("NegativeMemoryAddress", [pc, address]) => {
+                Self::NegativeMemoryAddress(pc.parse().ok()?, address.parse().ok()?)
+            }
+            ("ParameterModeInvalid", [address]) => {
+                Self::ParameterModeInvalid(address.parse().ok()?)
+            }
+            ("ProgramTooBig", [size]) => Self::ProgramTooBig(size.parse().ok()?),
+            ("StepLimitExceeded", [limit]) => Self::StepLimitExceeded(limit.parse().ok()?),
+            ("UninitializedOperation", [address]) => {
+                Self::UninitializedOperation(address.parse().ok()?)
+            }
+            ("UnknownOperation", [address, op]) => {
+                Self::UnknownOperation(address.parse().ok()?, op.parse().ok()?)
+            }
+            _ => return None,
+        };
+
+        Some(fault)
+    }
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyJournal => write!(f, "there are no journaled steps left to undo"),
+            Self::InvalidProgramCount(pc, target) => write!(
+                f,
+                "instruction at {} attempted to jump to invalid address {}",
+                pc, target
+            ),
+            Self::InvalidToken(index, token) => write!(
+                f,
+                "value `{}` at position {} isn't a valid number",
+                token, index
+            ),
+            Self::IoError(msg) => write!(f, "unable to load the program: {}", msg),
+            Self::MemoryExceeded => write!(f, "attempted to access memory beyond its end"),
+            Self::MisalignedJump(target) => write!(
+                f,
+                "jump target {} is in the middle of an instruction",
+                target
+            ),
+            Self::OutputOverflow => write!(f, "output buffer grew beyond its cap"),
+            Self::NegativeMemoryAddress(pc, address) => write!(
+                f,
+                "instruction at {} accessed negative address {}",
+                pc, address
+            ),
+            Self::ParameterModeInvalid(address) => write!(
+                f,
+                "instruction at {} uses an invalid parameter mode",
+                address
+            ),
+            Self::ProgramTooBig(size) => write!(
+                f,
+                "program of {} values doesn't fit in {} words of memory",
+                size, MEMORY_LIMIT
+            ),
+            Self::StepLimitExceeded(limit) => write!(
+                f,
+                "program neither halted nor waited on input within {} steps",
+                limit
+            ),
+            Self::UninitializedOperation(address) => write!(
+                f,
+                "attempted to execute uninitialized memory at {}",
+                address
+            ),
+            Self::UnknownOperation(address, op) => {
+                write!(f, "unknown operation {} at {}", op, address)
+            }
+        }
+    }
+}
+
+/// Faults carry everything needed to describe them, there is never an underlying cause to report.
+impl std::error::Error for Fault {}
+
+/// Something the computer can be wired up to with `run_with_io()`, rather than queueing up input
+/// and draining output by hand. This makes it possible to drive interactive programs that need
+/// to react to what the machine has said so far.
+pub trait IoPort {
+    /// Provides the next value for an input operation, returning None when there isn't anything
+    /// available yet which leaves the machine waiting on input.
+    fn read(&mut self) -> Option<isize>;
+
+    /// Receives each value the machine outputs, as soon as it is produced.
+    fn write(&mut self, value: isize);
+}
+
+/// A summary of the values present in a computer's memory. This is useful for getting an idea of
+/// the scale of the numbers a program works with, such as whether it is likely to overflow a
+/// particular word size. Uninitialized memory isn't considered by any of the fields.
+#[derive(Debug, PartialEq)]
+pub struct MemoryStats {
+    pub min: Option<isize>,
+    pub max: Option<isize>,
+
+    pub initialized: usize,
+    pub negative: usize,
+}
+
+/// Everything needed to revert the machine to the state it was in before a single step. Memory
+/// writes are recorded as the value that was in the cell before it was overwritten (which may
+/// have been uninitialized).
+#[derive(Clone, Debug, PartialEq)]
+struct StepDelta {
+    pc: usize,
+    relative_base: isize,
+    waiting_on_input: bool,
+
+    consumed_input: Option<isize>,
+    output_len: usize,
+    writes: Vec<(usize, Option<isize>)>,
+}
+
+/// Somewhere input can be lazily pulled from once the queued input has run out, see
+/// `set_input_source()`. Closures can't be cloned so a cloned machine starts out without one, it
+/// wouldn't make sense for two machines to be pulling from the same stream anyway.
+#[derive(Default)]
+struct InputSource(Option<Box<dyn FnMut() -> Option<isize>>>);
+
+impl Clone for InputSource {
+    fn clone(&self) -> Self {
+        Self(None)
+    }
+}
+
+impl InputSource {
+    fn pull(&mut self) -> Option<isize> {
+        self.0.as_mut().and_then(|source| source())
+    }
+}
+
+/// A checkpoint of a machine's execution state taken with `snapshot()`. Unlike `reset()`, which
+/// always goes back to the program as it was loaded, restoring one of these returns the machine to
+/// wherever it happened to be when the snapshot was taken.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pc: usize,
+    relative_base: isize,
+
+    input: Vec<isize>,
+    input_log: Vec<isize>,
+    memory: Vec<Option<isize>>,
+    output: Vec<isize>,
+
+    waiting_on_input: bool,
+}
+
 /// An IntCodeComputer emulator as defined in the day 2 segment of the 2019 Advent of Code.
+///
+/// Cloning a machine copies its entire state, including anything pending in its input and output
+/// buffers, so a clone can be used to explore a branch of execution without disturbing the
+/// original.
+#[derive(Clone)]
 pub struct IntCodeComputer {
     pc: usize,
+    relative_base: isize,
 
     input: Vec<isize>,
-    memory: [Option<isize>; MEMORY_SIZE],
+    input_log: Vec<isize>,
+    input_source: InputSource,
+    memory: Vec<Option<isize>>,
     output: Vec<isize>,
+    output_cap: Option<usize>,
 
     waiting_on_input: bool,
+    allow_extensions: bool,
+    strict_jumps: bool,
+
+    breakpoints: HashSet<usize>,
+
+    op_count: usize,
+    op_counts: HashMap<&'static str, usize>,
+    halt_counted: bool,
+
+    journaling: bool,
+    journal: Vec<StepDelta>,
+    pending_delta: Option<StepDelta>,
 
-    original_memory: [Option<isize>; MEMORY_SIZE],
+    original_memory: Vec<Option<isize>>,
 }
 
 impl IntCodeComputer {
+    /// Marks an address that `run_to_breakpoint()` should stop at once the program counter reaches
+    /// it. Breakpoints are part of the debugging setup rather than the machine state, so they
+    /// survive a `reset()`.
+    pub fn add_breakpoint(&mut self, address: usize) {
+        self.breakpoints.insert(address);
+    }
+
     pub fn add_input(&mut self, input: Vec<isize>) {
         // Rust doesn't have a shift/unshift method so we always will be working from the back of
         // the list. To get the correct order we need to reverse it when we initialize the
@@ -53,15 +305,15 @@ impl IntCodeComputer {
     /// come up later, I went ahead and implemented it.
     ///
     /// It's important to note that this allows exactly one invalid index intentionally (self.pc ==
-    /// MEMORY_SIZE). This is not a valid memory address but allows Halt to be the final
+    /// MEMORY_LIMIT). This is not a valid memory address but allows Halt to be the final
     /// instruction up against our memory limit (which I did define arbitrarily).
     pub fn advance(&mut self, amount: usize) -> Result<(), Fault> {
         let new_pc = self.pc + amount;
 
         // The less than here is intentional. We want to allow the program counter to be
-        // incremented 1 beyond the memory size so the last valid instruction is allowed to be a
+        // incremented 1 beyond the memory limit so the last valid instruction is allowed to be a
         // Halt. Any other instruction should still throw a memory error...
-        if new_pc > MEMORY_SIZE {
+        if new_pc > MEMORY_LIMIT {
             return Err(Fault::MemoryExceeded);
         }
 
@@ -69,20 +321,43 @@ impl IntCodeComputer {
         Ok(())
     }
 
+    /// Faults if the output buffer has grown beyond the cap set with `set_output_cap()`.
+    fn check_output_cap(&self) -> Result<(), Fault> {
+        if let Some(cap) = self.output_cap {
+            if self.output.len() > cap {
+                return Err(Fault::OutputOverflow);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Removes a breakpoint previously added with `add_breakpoint()`. Clearing an address that
+    /// doesn't have a breakpoint is a no-op.
+    pub fn clear_breakpoint(&mut self, address: usize) {
+        self.breakpoints.remove(&address);
+    }
+
     /// Decodes the operation pointed to by the program counter. Will fault if the operation is
     /// unknown or if the program as entered uninitialized memory.
     pub fn current_op(&self) -> Result<Operation, Fault> {
-        if self.pc >= MEMORY_SIZE {
+        self.decode_op(self.pc)
+    }
+
+    /// Decodes the operation stored at the provided address. Any faults will reference the
+    /// address being decoded rather than the program counter.
+    fn decode_op(&self, address: usize) -> Result<Operation, Fault> {
+        if address >= MEMORY_LIMIT {
             return Err(Fault::MemoryExceeded);
         }
 
-        match self.memory[self.pc] {
+        match self.memory.get(address).cloned().flatten() {
             Some(op) => {
                 let op_id = op % 100;
                 let parameter_mode = match (op / 100).try_into() {
                     Ok(pm) => pm,
                     Err(_) => {
-                        return Err(Fault::ParameterModeInvalid(self.pc));
+                        return Err(Fault::ParameterModeInvalid(address));
                     }
                 };
 
@@ -90,46 +365,267 @@ impl IntCodeComputer {
                     1 => Ok(Operation::Add(parameter_mode)),
                     2 => Ok(Operation::Mul(parameter_mode)),
                     3 => {
-                        if parameter_mode > 0 {
-                            return Err(Fault::ParameterModeInvalid(self.pc));
+                        // The only parameter is where the input gets written, which can't be
+                        // immediate
+                        if parameter_mode != 0 && parameter_mode != 2 {
+                            return Err(Fault::ParameterModeInvalid(address));
                         }
 
-                        Ok(Operation::Input)
+                        Ok(Operation::Input(parameter_mode))
                     }
                     4 => Ok(Operation::Output(parameter_mode)),
                     5 => Ok(Operation::JumpIfTrue(parameter_mode)),
                     6 => Ok(Operation::JumpIfFalse(parameter_mode)),
                     7 => Ok(Operation::LessThan(parameter_mode)),
                     8 => Ok(Operation::Equals(parameter_mode)),
+                    9 => Ok(Operation::AdjustRelativeBase(parameter_mode)),
+                    EXTENSION_HALT_WITH_CODE if self.allow_extensions => {
+                        Ok(Operation::HaltWithCode(parameter_mode))
+                    }
                     99 => {
                         if parameter_mode > 0 {
-                            return Err(Fault::ParameterModeInvalid(self.pc));
+                            return Err(Fault::ParameterModeInvalid(address));
                         }
 
                         Ok(Operation::Halt)
                     }
-                    _ => Err(Fault::UnknownOperation(self.pc, op)),
+                    _ => Err(Fault::UnknownOperation(address, op)),
+                }
+            }
+            None => Err(Fault::UninitializedOperation(address)),
+        }
+    }
+
+    /// Walks the program from address 0 rendering each instruction as a line of assembly, such as
+    /// `0000: ADD [4] #3 -> [5]`. Position mode parameters are shown as `[n]`, immediate ones as
+    /// `#n` and relative ones as `[rb+n]`. Anything that doesn't decode to a known operation is
+    /// shown as `DATA n` and skipped over one cell at a time. The walk stops after the first halt
+    /// or at the first uninitialized cell.
+    ///
+    /// Like `is_parameter_address()` this is a linear sweep, so data embedded between instructions
+    /// or self modifying code may not be rendered the way it would actually execute.
+    pub fn disassemble(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut address = 0;
+
+        while address < self.memory.len() {
+            let op = match self.decode_op(address) {
+                Ok(op) => op,
+                Err(Fault::UnknownOperation(_, _)) | Err(Fault::ParameterModeInvalid(_)) => {
+                    let val = self.memory[address].unwrap_or(0);
+                    lines.push(format!("{:04}: DATA {}", address, val));
+                    address += 1;
+                    continue;
+                }
+                Err(_) => break,
+            };
+
+            let modes = match op {
+                Operation::Add(pm)
+                | Operation::Mul(pm)
+                | Operation::Input(pm)
+                | Operation::Output(pm)
+                | Operation::JumpIfTrue(pm)
+                | Operation::JumpIfFalse(pm)
+                | Operation::LessThan(pm)
+                | Operation::Equals(pm)
+                | Operation::AdjustRelativeBase(pm)
+                | Operation::HaltWithCode(pm) => pm,
+                Operation::Halt => 0,
+            };
+
+            let mut params: Vec<String> = (0..op.arity())
+                .map(|idx| {
+                    let mode = (modes / 10usize.pow(idx as u32)) % 10;
+                    self.format_parameter(address + idx + 1, mode)
+                })
+                .collect();
+
+            // The destination of operations that write to memory is always their last parameter
+            if op.writes_memory() {
+                if let Some(dest) = params.pop() {
+                    params.push(format!("-> {}", dest));
                 }
             }
-            None => Err(Fault::UninitializedOperation(self.pc)),
+
+            let mut line = format!("{:04}: {}", address, op.mnemonic());
+            for param in params {
+                line.push(' ');
+                line.push_str(&param);
+            }
+            lines.push(line);
+
+            if matches!(op, Operation::Halt | Operation::HaltWithCode(_)) {
+                break;
+            }
+
+            address += op.instruction_size();
         }
+
+        lines
     }
 
-    /// Initialize a new IntCodeComputer emulator with the provided memory. This must be a slice
-    /// equal in size to `MEMORY_SIZE`.
-    pub fn new(memory: [Option<isize>; MEMORY_SIZE]) -> Self {
+    /// Renders a single parameter of an instruction for the disassembler using the notation for
+    /// its mode. Modes that aren't valid are marked with a `?` so they stand out.
+    fn format_parameter(&self, address: usize, mode: usize) -> String {
+        let raw = self.memory.get(address).cloned().flatten().unwrap_or(0);
+
+        match mode {
+            0 => format!("[{}]", raw),
+            1 => format!("#{}", raw),
+            2 if raw < 0 => format!("[rb{}]", raw),
+            2 => format!("[rb+{}]", raw),
+            _ => format!("?{}", raw),
+        }
+    }
+
+    /// Produces a line for every memory cell from `start` up to but not including `end`, in the
+    /// form `address: value` with `uninit` standing in for uninitialized cells. Unlike
+    /// `memory_str()` every address is accounted for, so two dumps can be compared line by line
+    /// with `diff`. The range is clipped to the end of the memory allocated so far.
+    pub fn dump(&self, start: usize, end: usize) -> String {
+        let end = end.min(self.memory.len());
+        let mut output = String::new();
+
+        for address in start..end {
+            let line = match self.memory[address] {
+                Some(val) => format!("{}: {}\n", address, val),
+                None => format!("{}: uninit\n", address),
+            };
+
+            output.push_str(&line);
+        }
+
+        output
+    }
+
+    /// Turns on the recording of each step's changes so they can later be reverted with
+    /// `step_back()`. This isn't free as every step allocates a small record, so it is disabled by
+    /// default. Disabling journaling throws away anything that has been recorded so far.
+    pub fn enable_journaling(&mut self, enabled: bool) {
+        self.journaling = enabled;
+
+        if !enabled {
+            self.journal.clear();
+        }
+    }
+
+    /// When the machine has halted using the halt with exit code extension this returns the exit
+    /// code it provided. This will be None for machines that haven't halted or halted normally.
+    pub fn exit_code(&self) -> Option<isize> {
+        match self.current_op() {
+            Ok(Operation::HaltWithCode(pm)) => {
+                let i_pc: isize = self.pc.try_into().ok()?;
+                self.retrieve(i_pc + 1, pm % 10).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// A chainable version of `add_input()`, allowing input to be queued and the program run in a
+    /// single expression such as `icc.feed(vec![5]).run()?`.
+    pub fn feed(&mut self, input: Vec<isize>) -> &mut Self {
+        self.add_input(input);
+        self
+    }
+
+    /// Parses a program from a buffered reader one value at a time, so the program never needs to
+    /// be held in memory as a single string. This accepts the same format as `from_str()`.
+    /// Failing to read from the reader will fault with an `IoError` describing the problem, while
+    /// a value that isn't a number faults with an `InvalidToken` just like `from_str()`.
+    pub fn from_reader(mut reader: impl BufRead) -> Result<Self, Fault> {
+        let mut memory: Vec<Option<isize>> = Vec::new();
+        let mut count = 0;
+        let mut chunk = Vec::new();
+
+        loop {
+            chunk.clear();
+            reader
+                .read_until(b',', &mut chunk)
+                .map_err(|err| Fault::IoError(err.to_string()))?;
+
+            // Whatever follows the last comma is the final chunk, even when there is nothing there.
+            // That way an empty reader or a trailing comma are rejected just like `from_str()`.
+            let last_chunk = chunk.last() != Some(&b',');
+            if !last_chunk {
+                chunk.pop();
+            }
+
+            for value in parse_chunk(&String::from_utf8_lossy(&chunk), count)? {
+                // Keep counting once we're out of memory so the fault can report the full size
+                if count < MEMORY_LIMIT {
+                    memory.push(Some(value));
+                }
+                count += 1;
+            }
+
+            if last_chunk {
+                break;
+            }
+        }
+
+        if count > MEMORY_LIMIT {
+            return Err(Fault::ProgramTooBig(count));
+        }
+
+        Ok(IntCodeComputer::new(memory))
+    }
+
+    /// Builds a machine directly from the raw values of a program, which is handy for tests and
+    /// generated programs that would otherwise need to be formatted into a string just to be
+    /// parsed right back out again. This has the same size limit as `from_str()`.
+    pub fn from_slice(program: &[isize]) -> Result<Self, Fault> {
+        if program.len() > MEMORY_LIMIT {
+            return Err(Fault::ProgramTooBig(program.len()));
+        }
+
+        Ok(IntCodeComputer::new(
+            program.iter().map(|val| Some(*val)).collect(),
+        ))
+    }
+
+    /// Initialize a new IntCodeComputer emulator with the provided memory. Anything shorter than
+    /// `MEMORY_SIZE` is padded out to it with uninitialized memory.
+    pub fn new(mut memory: Vec<Option<isize>>) -> Self {
+        if memory.len() < MEMORY_SIZE {
+            memory.resize(MEMORY_SIZE, None);
+        }
+
         Self {
             pc: 0,
+            relative_base: 0,
 
             input: Vec::new(),
-            memory,
+            input_log: Vec::new(),
+            input_source: InputSource::default(),
+            memory: memory.clone(),
             output: Vec::new(),
+            output_cap: None,
 
             waiting_on_input: false,
+            allow_extensions: false,
+            strict_jumps: false,
+
+            breakpoints: HashSet::new(),
+
+            op_count: 0,
+            op_counts: HashMap::new(),
+            halt_counted: false,
+
+            journaling: false,
+            journal: Vec::new(),
+            pending_delta: None,
+
             original_memory: memory,
         }
     }
 
+    /// Drains everything this machine has output so far into the input queue of another machine,
+    /// preserving the order it was produced in. This is the glue needed to chain machines together.
+    pub fn pipe_output_into(&mut self, other: &mut IntCodeComputer) {
+        other.add_input(self.output());
+    }
+
     /// The advent challenge refers to this as the instruction pointer the computer is currently
     /// at, but I prefer the more traditional program counter or `pc`. This retrieves the location
     /// in memory the program is currently executing or about to execute.
@@ -137,18 +633,130 @@ impl IntCodeComputer {
         self.pc
     }
 
+    /// The current value of the relative base register used by relative mode (2) parameters. This
+    /// starts at 0 and is only ever changed by the `AdjustRelativeBase` operation.
+    pub fn relative_base(&self) -> isize {
+        self.relative_base
+    }
+
+    /// Interactive programs read their input as ASCII one line at a time. This queues up the
+    /// characters of the provided line followed by the newline that terminates it. Multiple lines
+    /// can be queued before running and will be consumed in the order they were provided.
+    pub fn queue_line(&mut self, line: &str) {
+        let mut input: Vec<isize> = line.bytes().map(|b| b as isize).collect();
+        input.push(b'\n' as isize);
+
+        self.add_input(input);
+    }
+
     /// A helper function for determining whether or not the machine has hit a valid halt state.
     /// This will not trip for errors, instead the result state of a step() should be checked to
-    /// see if an error occured. Attempted execution after an error or halt occurs is undefined
-    /// behavior.
+    /// see if an error occured. Attempted execution after an error is undefined behavior, but
+    /// stepping or running a halted machine is a no-op.
     pub fn is_halted(&self) -> bool {
-        self.current_op() == Ok(Operation::Halt)
+        matches!(
+            self.current_op(),
+            Ok(Operation::Halt) | Ok(Operation::HaltWithCode(_))
+        )
+    }
+
+    /// Performs a linear sweep over the program from address 0, decoding each instruction in turn,
+    /// to determine whether the provided address is one of the parameters of an instruction
+    /// rather than the start of one. The sweep stops at the first halt or at anything it can't
+    /// decode as everything after that point is considered data.
+    ///
+    /// Since programs are able to modify themselves this is only a best effort classification of
+    /// the current memory state.
+    fn is_parameter_address(&self, address: usize) -> bool {
+        let mut current = 0;
+
+        while current <= address {
+            let op = match self.decode_op(current) {
+                Ok(Operation::Halt) | Ok(Operation::HaltWithCode(_)) | Err(_) => return false,
+                Ok(op) => op,
+            };
+
+            let next = current + op.instruction_size();
+            if current < address && address < next {
+                return true;
+            }
+
+            current = next;
+        }
+
+        false
+    }
+
+    /// Every input value the program has consumed so far, in the order it was consumed. Unlike the
+    /// pending input this is never drained, so feeding it to a fresh copy of the program with
+    /// `add_input()` will replay the same session.
+    pub fn input_log(&self) -> &[isize] {
+        &self.input_log
+    }
+
+    /// The number of instructions the machine has executed since it was created or last reset,
+    /// which makes for a rough measure of how efficient a program is. Waiting on input doesn't
+    /// count as executing anything, and the final halt is counted once when it is executed no
+    /// matter how many times the machine is stepped afterwards.
+    pub fn instructions_executed(&self) -> usize {
+        self.op_count
     }
 
     pub fn is_waiting_on_input(&self) -> bool {
         self.waiting_on_input
     }
 
+    /// Moves the program counter to the target of a jump. When strict jumps are enabled this will
+    /// fault if the target lands on the parameter of an instruction instead of the start of one.
+    fn jump_to(&mut self, new_pc: isize) -> Result<(), Fault> {
+        let target: usize = match new_pc.try_into() {
+            Ok(pc) => pc,
+            Err(_) => {
+                return Err(Fault::InvalidProgramCount(self.pc, new_pc));
+            }
+        };
+
+        if self.strict_jumps && self.is_parameter_address(target) {
+            return Err(Fault::MisalignedJump(target));
+        }
+
+        self.pc = target;
+        Ok(())
+    }
+
+    /// Checks whether two machines have exactly the same values at the same addresses, ignoring
+    /// everything else about their state such as the program counter and any pending input or
+    /// output. Unlike comparing `memory_str()` this isn't fooled by values that line up once the
+    /// gaps between them are collapsed, and how far memory has grown doesn't matter as long as
+    /// the extra space was never written to.
+    pub fn memory_equals(&self, other: &IntCodeComputer) -> bool {
+        self.memory_map() == other.memory_map()
+    }
+
+    /// Pairs every initialized memory cell with its address. Unlike `memory_str()` nothing moves
+    /// around when there are gaps of uninitialized memory, so this is a faithful picture of where
+    /// everything lives.
+    pub fn memory_map(&self) -> Vec<(usize, isize)> {
+        self.memory
+            .iter()
+            .enumerate()
+            .filter_map(|(address, cell)| cell.map(|val| (address, val)))
+            .collect()
+    }
+
+    /// Collects statistics over the current contents of memory without modifying the machine.
+    pub fn memory_stats(&self) -> MemoryStats {
+        let values = self.memory.iter().filter_map(|m| m.as_ref());
+
+        MemoryStats {
+            min: values.clone().min().cloned(),
+            max: values.clone().max().cloned(),
+
+            initialized: values.clone().count(),
+            negative: values.filter(|v| **v < 0).count(),
+        }
+    }
+
     /// Convert the internal memory representation into the format used by the Advent examples.
     ///
     /// The challenge doesn't specify the value of uninitialized memory or have a representation of
@@ -168,7 +776,8 @@ impl IntCodeComputer {
     }
 
     /// Safely returns the value stored at the provided memory address. Will fault in the event of
-    /// invalid addresses or uninitialized memory.
+    /// invalid addresses. Day 9 clarified that memory beyond the program starts out as 0, so
+    /// reading uninitialized memory returns that rather than faulting.
     pub fn mem_read(&self, address: isize) -> Result<isize, Fault> {
         let safe_address: usize = match address.try_into() {
             Ok(val) => val,
@@ -179,35 +788,115 @@ impl IntCodeComputer {
             }
         };
 
-        if safe_address >= MEMORY_SIZE {
+        if safe_address >= MEMORY_LIMIT {
             return Err(Fault::MemoryExceeded);
         }
 
-        match self.memory[safe_address] {
+        match self.memory.get(safe_address).cloned().flatten() {
             Some(val) => Ok(val),
-            None => Err(Fault::MissingMemory(self.pc, safe_address)),
+            None => Ok(0),
         }
     }
 
+    /// Breaks down `instructions_executed()` by the mnemonic of each instruction, handy for
+    /// spotting where a program spends its time.
+    pub fn op_histogram(&self) -> HashMap<&'static str, usize> {
+        self.op_counts.clone()
+    }
+
     pub fn output(&mut self) -> Vec<isize> {
         let current_out = self.output.clone();
         self.output = Vec::new();
         current_out
     }
 
+    /// Drains the output just like `output()` but renders it as text, which is how a lot of the
+    /// later puzzles talk. Values in the ASCII range become the matching character while anything
+    /// else, such as a final answer too big to be a character, is shown as `<n>`.
+    pub fn output_as_string(&mut self) -> String {
+        self.output()
+            .into_iter()
+            .map(|val| match val {
+                0..=127 => (val as u8 as char).to_string(),
+                _ => format!("<{}>", val),
+            })
+            .collect()
+    }
+
+    /// Everything output since the buffer was last drained, without draining it.
+    pub fn peek_output(&self) -> &[isize] {
+        &self.output
+    }
+
+    /// The number of input values queued up that the program hasn't consumed yet.
+    pub fn pending_input_len(&self) -> usize {
+        self.input.len()
+    }
+
+    /// The number of output values sitting in the buffer waiting to be collected.
+    pub fn pending_output_len(&self) -> usize {
+        self.output.len()
+    }
+
     /// Resets the computer to the initial state it was created with and resets the program counter
     /// to 0.
     pub fn reset(&mut self) {
         self.pc = 0;
+        self.relative_base = 0;
 
         self.input = Vec::new();
-        self.memory = self.original_memory;
+        self.input_log = Vec::new();
+        self.input_source = InputSource::default();
+        self.memory = self.original_memory.clone();
         self.output = Vec::new();
 
         self.waiting_on_input = false;
+        self.journal.clear();
+
+        self.op_count = 0;
+        self.op_counts.clear();
+        self.halt_counted = false;
+    }
+
+    /// Tallies up an instruction that was just executed.
+    fn record_op(&mut self, mnemonic: &'static str) {
+        self.op_count += 1;
+        *self.op_counts.entry(mnemonic).or_insert(0) += 1;
+    }
+
+    /// Tallies up the halt the machine is sitting on, if it is sitting on one. None of the run
+    /// loops step a machine once it has reached a halt, so landing on one is when it counts as
+    /// executed. It only ever counts once no matter how many more times the machine is stepped.
+    fn record_halt(&mut self) {
+        if self.halt_counted {
+            return;
+        }
+
+        if let Ok(op @ (Operation::Halt | Operation::HaltWithCode(_))) = self.current_op() {
+            self.halt_counted = true;
+            self.record_op(op.mnemonic());
+        }
     }
 
-    // Performs a parameter read using the provided access mode (0 - Position, 1 - Immediate)
+    /// Returns the machine to the state captured in a previous `snapshot()`. The journal only
+    /// describes how we got to the state being thrown away so it is cleared, stepping back past
+    /// the restored point isn't possible.
+    pub fn restore(&mut self, snap: &Snapshot) {
+        self.pc = snap.pc;
+        self.relative_base = snap.relative_base;
+
+        self.input = snap.input.clone();
+        self.input_log = snap.input_log.clone();
+        self.memory = snap.memory.clone();
+        self.output = snap.output.clone();
+
+        self.waiting_on_input = snap.waiting_on_input;
+        self.journal.clear();
+        self.halt_counted = false;
+    }
+
+    // Performs a parameter read using the provided access mode (0 - Position, 1 - Immediate,
+    // 2 - Relative)
     pub fn retrieve(&self, address: isize, read_mode: usize) -> Result<isize, Fault> {
         let raw_mem = self.mem_read(address)?;
         match read_mode {
@@ -217,28 +906,174 @@ impl IntCodeComputer {
             // Immediate mode, return the value at the parameter's location
             1 => Ok(raw_mem),
 
+            // Relative mode, like position mode but the address is offset by the relative base
+            2 => Ok(self.mem_read(self.relative_base + raw_mem)?),
+
             // All other modes are invalid
             _ => Err(Fault::ParameterModeInvalid(self.pc)),
         }
     }
 
-    /// Run the computer until it reaches a halt (success), or a fault (failure). If there was a
-    /// more complicated instruction set that involved jumps I would likely want to limit the
-    /// runtime of this to a certain number of instructions to ensure it always completed, but as
-    /// it stands it can at most execute MEMORY_SIZE / 4 instructions before exiting.
+    /// Run the computer until it reaches a halt (success), or a fault (failure). Now that there
+    /// are jumps a program can happily loop forever, so this will never return for those. When
+    /// the program isn't trusted `run_with_limit()` should be used instead.
     pub fn run(&mut self) -> Result<(), Fault> {
         loop {
+            if self.is_halted() || self.is_waiting_on_input() {
+                return Ok(());
+            }
+
+            self.step()?;
+            self.check_output_cap()?;
+        }
+    }
+
+    /// Steps the computer until it produces exactly one new output value and returns it, pausing
+    /// right after the output operation. The value is taken out of the output buffer but anything
+    /// that was already sitting there is left alone. Returns None if the machine halts or stops to
+    /// wait on input before producing anything.
+    pub fn run_until_output(&mut self) -> Result<Option<isize>, Fault> {
+        let output_len = self.output.len();
+
+        loop {
+            if self.is_halted() || self.is_waiting_on_input() {
+                return Ok(None);
+            }
+
+            self.step()?;
+
+            if self.output.len() > output_len {
+                return Ok(self.output.pop());
+            }
+        }
+    }
+
+    /// Runs the computer just like `run()` but pulls input from and pushes output to the provided
+    /// port. Anything already queued with `add_input()` is consumed first, and only once that has
+    /// run out is the port asked for more. An input source set with `set_input_source()` counts as
+    /// part of the machine's own input and is tried before the port. The machine only stops to
+    /// wait on input when the port doesn't have anything to give it.
+    pub fn run_with_io(&mut self, io: &mut dyn IoPort) -> Result<(), Fault> {
+        loop {
+            if self.is_halted() {
+                return Ok(());
+            }
+
+            if matches!(self.current_op()?, Operation::Input(_)) && self.input.is_empty() {
+                match self.input_source.pull().or_else(|| io.read()) {
+                    Some(val) => self.add_input(vec![val]),
+                    None => {
+                        self.waiting_on_input = true;
+                        return Ok(());
+                    }
+                }
+            }
+
             self.step()?;
 
+            for val in self.output.drain(..) {
+                io.write(val);
+            }
+        }
+    }
+
+    /// Runs the computer just like `run()` but will only step it at most `max_steps` times. If
+    /// the machine hasn't halted or stopped to wait on input by then this faults with
+    /// `StepLimitExceeded`, leaving the machine where it stopped so it can be inspected.
+    pub fn run_with_limit(&mut self, max_steps: usize) -> Result<(), Fault> {
+        for _ in 0..max_steps {
             if self.is_halted() || self.is_waiting_on_input() {
                 return Ok(());
             }
+
+            self.step()?;
+            self.check_output_cap()?;
+        }
+
+        if self.is_halted() || self.is_waiting_on_input() {
+            return Ok(());
+        }
+
+        Err(Fault::StepLimitExceeded(max_steps))
+    }
+
+    /// Runs the computer just like `run()` but stops early once the program counter lands on one
+    /// of the breakpoints, before the instruction there has been executed. Returns `true` when a
+    /// breakpoint was hit and `false` when the machine halted or is waiting on input.
+    ///
+    /// At least one step is always taken, so calling this again while sitting on a breakpoint
+    /// continues on to the next one rather than getting stuck. The machine can also be resumed
+    /// with `run()` which ignores breakpoints entirely.
+    pub fn run_to_breakpoint(&mut self) -> Result<bool, Fault> {
+        loop {
+            if self.is_halted() || self.is_waiting_on_input() {
+                return Ok(false);
+            }
+
+            self.step()?;
+            self.check_output_cap()?;
+
+            if self.breakpoints.contains(&self.pc) {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Enables the decoding of non-standard extension operations such as the halt with exit code
+    /// operation. These are disabled by default so standard programs are never affected by them.
+    pub fn set_allow_extensions(&mut self, allow: bool) {
+        self.allow_extensions = allow;
+    }
+
+    /// Provides somewhere to lazily pull input from whenever the program wants more than has been
+    /// queued up with `add_input()`, which is useful when the input is generated or never ends.
+    /// Queued input is always used first. When the source returns None the machine waits on input
+    /// the same as it would with an empty queue, and is able to try the source again once it is
+    /// given input or another source. A `reset()` removes the source.
+    pub fn set_input_source(&mut self, source: Box<dyn FnMut() -> Option<isize>>) {
+        self.input_source = InputSource(Some(source));
+        self.waiting_on_input = false;
+    }
+
+    /// Limits the number of output values that can be buffered while running. A program that
+    /// never halts but continuously produces output would otherwise grow the buffer without bound,
+    /// with a cap set `run()` will instead fault once the buffer grows beyond it. The output is
+    /// unbounded by default (`None`).
+    pub fn set_output_cap(&mut self, cap: Option<usize>) {
+        self.output_cap = cap;
+    }
+
+    /// IntCode programs are able to modify themselves and nothing prevents a jump from landing in
+    /// the middle of an instruction. Well formed programs shouldn't do this though, so when
+    /// debugging it can be useful to treat it as a fault. This is disabled by default.
+    pub fn set_strict_jumps(&mut self, strict: bool) {
+        self.strict_jumps = strict;
+    }
+
+    /// Captures the current execution state so it can be returned to later with `restore()`. This
+    /// is cheaper than cloning the whole machine when exploring several branches from a single
+    /// point, as the configuration and debugging setup don't need to be copied along with it.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            pc: self.pc,
+            relative_base: self.relative_base,
+
+            input: self.input.clone(),
+            input_log: self.input_log.clone(),
+            memory: self.memory.clone(),
+            output: self.output.clone(),
+
+            waiting_on_input: self.waiting_on_input,
         }
     }
 
     /// Steps the state of the computer by performing one operation and advancing the program
     /// counter an appropriate amount. Will fault if the current program counter, any parameters,
     /// or target addresses are outside of the valid memory range or are uninitialized.
+    ///
+    /// Once the machine has reached a halt instruction stepping is a no-op. The program counter
+    /// stays pointed at the halt so the machine remains halted no matter how many more times it
+    /// is stepped.
     pub fn step(&mut self) -> Result<(), Fault> {
         if self.is_waiting_on_input() {
             return Ok(());
@@ -248,7 +1083,115 @@ impl IntCodeComputer {
         // current program counter is pointing at may have been modified. We need the original
         // instruction to ensure we correctly advance to the next program state.
         let current_op = self.current_op()?;
+        let mnemonic = current_op.mnemonic();
+        let halting = matches!(current_op, Operation::Halt | Operation::HaltWithCode(_));
+
+        // Gathering up the parameters isn't free, so don't bother unless someone will see them
+        if log_enabled!(Level::Debug) {
+            let params: Vec<isize> = (1..=current_op.arity())
+                .map(|offset| {
+                    self.memory
+                        .get(self.pc + offset)
+                        .cloned()
+                        .flatten()
+                        .unwrap_or(0)
+                })
+                .collect();
+
+            debug!("{:04}: {:?} {:?}", self.pc, current_op, params);
+        }
+
+        // Halting is a no-op so there is nothing worth journaling, this also means stepping back
+        // from a halted machine undoes the last instruction that actually did something. The halt
+        // itself only counts as executed the first time we reach it.
+        if halting {
+            self.record_halt();
+            return Ok(());
+        }
+
+        if !self.journaling {
+            let result = self.execute(current_op);
+            if result.is_ok() && !self.waiting_on_input {
+                self.record_op(mnemonic);
+                self.record_halt();
+            }
+
+            return result;
+        }
+
+        let input_log_len = self.input_log.len();
+
+        self.pending_delta = Some(StepDelta {
+            pc: self.pc,
+            relative_base: self.relative_base,
+            waiting_on_input: self.waiting_on_input,
+
+            consumed_input: None,
+            output_len: self.output.len(),
+            writes: Vec::new(),
+        });
+
+        let result = self.execute(current_op);
+
+        // An input instruction that found nothing to read didn't actually do anything, it will be
+        // executed again once there is input. Recording it would leave the next `step_back()`
+        // undoing the wait rather than the last instruction that did something.
+        let executed = result.is_ok() && !self.waiting_on_input;
+
+        if let Some(mut delta) = self.pending_delta.take() {
+            // A partially executed step isn't something we can sensibly revert to, so only
+            // successful steps make it into the journal.
+            if executed {
+                // Input may have come from the source rather than the queue, but either way it
+                // ends up in the log
+                if self.input_log.len() > input_log_len {
+                    delta.consumed_input = self.input_log.last().cloned();
+                }
+
+                self.journal.push(delta);
+            }
+        }
+
+        if executed {
+            self.record_op(mnemonic);
+            self.record_halt();
+        }
+
+        result
+    }
+
+    /// Reverts the most recent journaled step, restoring the program counter, any memory it wrote
+    /// to and any input it consumed. Output that was already collected with `output()` can't be
+    /// taken back, only output still sitting in the buffer is removed. This will fault with
+    /// `EmptyJournal` if there is nothing left to undo or journaling was never enabled.
+    pub fn step_back(&mut self) -> Result<(), Fault> {
+        let delta = match self.journal.pop() {
+            Some(delta) => delta,
+            None => return Err(Fault::EmptyJournal),
+        };
+
+        for (address, previous) in delta.writes.into_iter().rev() {
+            self.memory[address] = previous;
+        }
+
+        if let Some(input) = delta.consumed_input {
+            // Input is stored reversed so the next value to be consumed lives at the back
+            self.input.push(input);
+            self.input_log.pop();
+        }
 
+        self.output.truncate(delta.output_len);
+        self.waiting_on_input = delta.waiting_on_input;
+        self.relative_base = delta.relative_base;
+        self.pc = delta.pc;
+        self.halt_counted = false;
+
+        Ok(())
+    }
+
+    /// Performs the already decoded operation at the current program counter, this is the bulk of
+    /// `step()` without any of the bookkeeping around it.
+    fn execute(&mut self, current_op: Operation) -> Result<(), Fault> {
         // Super unlikely this fails, it will only do so if the PC is >= 2^63
         let i_pc: isize = self.pc.try_into().unwrap();
 
@@ -256,19 +1199,19 @@ impl IntCodeComputer {
             Operation::Add(pm) => {
                 let left_val = self.retrieve(i_pc + 1, pm % 10)?;
                 let right_val = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                let dest_addr = self.retrieve(i_pc + 3, 1)?;
+                let dest_addr = self.write_address(i_pc + 3, (pm / 100) % 10)?;
 
                 self.store(dest_addr, left_val + right_val)?;
             }
             Operation::Mul(pm) => {
                 let left_val = self.retrieve(i_pc + 1, pm % 10)?;
                 let right_val = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                let dest_addr = self.retrieve(i_pc + 3, 1)?;
+                let dest_addr = self.write_address(i_pc + 3, (pm / 100) % 10)?;
 
                 self.store(dest_addr, left_val * right_val)?;
             }
-            Operation::Input => {
-                let input = match self.input.pop() {
+            Operation::Input(pm) => {
+                let input = match self.input.pop().or_else(|| self.input_source.pull()) {
                     Some(val) => val,
                     None => {
                         // We need to pause operations to wait for additional input
@@ -277,8 +1220,9 @@ impl IntCodeComputer {
                     }
                 };
 
-                let dest_addr = self.retrieve(i_pc + 1, 1)?;
+                let dest_addr = self.write_address(i_pc + 1, pm % 10)?;
                 self.store(dest_addr, input)?;
+                self.input_log.push(input);
             }
             Operation::Output(pm) => {
                 let output_val = self.retrieve(i_pc + 1, pm % 10)?;
@@ -289,12 +1233,7 @@ impl IntCodeComputer {
 
                 if conditional != 0 {
                     let new_pc = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                    self.pc = match new_pc.try_into() {
-                        Ok(pc) => pc,
-                        Err(_) => {
-                            return Err(Fault::InvalidProgramCount(self.pc, new_pc));
-                        }
-                    };
+                    self.jump_to(new_pc)?;
 
                     // Ensure we skip the op advancement when we modify the PC
                     return Ok(());
@@ -305,12 +1244,7 @@ impl IntCodeComputer {
 
                 if conditional == 0 {
                     let new_pc = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                    self.pc = match new_pc.try_into() {
-                        Ok(pc) => pc,
-                        Err(_) => {
-                            return Err(Fault::InvalidProgramCount(self.pc, new_pc));
-                        }
-                    };
+                    self.jump_to(new_pc)?;
 
                     // Ensure we skip the op advancement when we modify the PC
                     return Ok(());
@@ -319,7 +1253,7 @@ impl IntCodeComputer {
             Operation::LessThan(pm) => {
                 let left_val = self.retrieve(i_pc + 1, pm % 10)?;
                 let right_val = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                let dest_addr = self.retrieve(i_pc + 3, 1)?;
+                let dest_addr = self.write_address(i_pc + 3, (pm / 100) % 10)?;
 
                 if left_val < right_val {
                     self.store(dest_addr, 1)?;
@@ -330,7 +1264,7 @@ impl IntCodeComputer {
             Operation::Equals(pm) => {
                 let left_val = self.retrieve(i_pc + 1, pm % 10)?;
                 let right_val = self.retrieve(i_pc + 2, (pm / 10) % 10)?;
-                let dest_addr = self.retrieve(i_pc + 3, 1)?;
+                let dest_addr = self.write_address(i_pc + 3, (pm / 100) % 10)?;
 
                 if left_val == right_val {
                     self.store(dest_addr, 1)?;
@@ -338,7 +1272,15 @@ impl IntCodeComputer {
                     self.store(dest_addr, 0)?;
                 }
             }
-            Operation::Halt => (),
+            Operation::AdjustRelativeBase(pm) => {
+                let offset = self.retrieve(i_pc + 1, pm % 10)?;
+                self.relative_base += offset;
+            }
+            Operation::Halt | Operation::HaltWithCode(_) => {
+                // Halting doesn't advance the program counter, otherwise we'd walk off into
+                // whatever happens to follow the halt the next time we're stepped.
+                return Ok(());
+            }
         }
 
         // Note: Depending on the instructions added in the future I may need to move this into the
@@ -348,8 +1290,8 @@ impl IntCodeComputer {
         Ok(())
     }
 
-    /// Safely stores the provided value at the provided address. This will fault only if the
-    /// memory address is invalid.
+    /// Safely stores the provided value at the provided address, growing memory to fit it if
+    /// needed. This will fault only if the memory address is invalid.
     pub fn store(&mut self, address: isize, value: isize) -> Result<(), Fault> {
         let safe_address: usize = match address.try_into() {
             Ok(val) => val,
@@ -360,13 +1302,41 @@ impl IntCodeComputer {
             }
         };
 
-        if safe_address >= MEMORY_SIZE {
+        if safe_address >= MEMORY_LIMIT {
             return Err(Fault::MemoryExceeded);
         }
 
+        if safe_address >= self.memory.len() {
+            self.memory.resize(safe_address + 1, None);
+        }
+
+        if let Some(delta) = self.pending_delta.as_mut() {
+            delta.writes.push((safe_address, self.memory[safe_address]));
+        }
+
         self.memory[safe_address] = Some(value);
         Ok(())
     }
+
+    /// Resolves the address a parameter at the provided location refers to when it is the
+    /// destination of a write. Position mode (0) uses the parameter as the address directly and
+    /// relative mode (2) offsets it by the relative base. Writing to an immediate value doesn't
+    /// make any sense so every other mode is invalid.
+    fn write_address(&self, address: isize, write_mode: usize) -> Result<isize, Fault> {
+        let raw_mem = self.mem_read(address)?;
+        match write_mode {
+            0 => Ok(raw_mem),
+            2 => Ok(self.relative_base + raw_mem),
+            _ => Err(Fault::ParameterModeInvalid(self.pc)),
+        }
+    }
+
+    /// A consuming builder style version of `add_input()`, useful for setting up a freshly parsed
+    /// machine with its input in one go.
+    pub fn with_input(mut self, input: Vec<isize>) -> Self {
+        self.add_input(input);
+        self
+    }
 }
 
 impl Default for IntCodeComputer {
@@ -376,13 +1346,30 @@ impl Default for IntCodeComputer {
     fn default() -> Self {
         IntCodeComputer {
             pc: 0,
+            relative_base: 0,
 
             input: Vec::new(),
-            memory: [None; MEMORY_SIZE],
+            input_log: Vec::new(),
+            input_source: InputSource::default(),
+            memory: vec![None; MEMORY_SIZE],
             output: Vec::new(),
+            output_cap: None,
 
             waiting_on_input: false,
-            original_memory: [None; MEMORY_SIZE],
+            allow_extensions: false,
+            strict_jumps: false,
+
+            breakpoints: HashSet::new(),
+
+            op_count: 0,
+            op_counts: HashMap::new(),
+            halt_counted: false,
+
+            journaling: false,
+            journal: Vec::new(),
+            pending_delta: None,
+
+            original_memory: vec![None; MEMORY_SIZE],
         }
     }
 }
@@ -391,23 +1378,110 @@ impl FromStr for IntCodeComputer {
     type Err = Fault;
 
     /// This parses the official Advent of Code 2019 program code for IntCodeComputer as defined up
-    /// to the end of day 2 and returns an instance of the emulator that can be run. This expects
-    /// only positive integer numbers on a single line separated by spaces.
+    /// to the end of day 2 and returns an instance of the emulator that can be run. The values are
+    /// normally separated by commas, but some shared examples use spaces or newlines instead so
+    /// any whitespace between values works as well. Anything that isn't a number, including the
+    /// empty value left by a trailing comma, faults with an `InvalidToken`.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let raw_mem: Vec<Option<isize>> = s
-            .trim()
-            .split(',')
-            .map(|s| Some(s.parse::<isize>().unwrap()))
-            .collect();
-        if raw_mem.len() > MEMORY_SIZE {
+        let mut raw_mem: Vec<Option<isize>> = Vec::new();
+
+        for chunk in s.trim().split(',') {
+            let values = parse_chunk(chunk, raw_mem.len())?;
+            raw_mem.extend(values.into_iter().map(Some));
+        }
+
+        if raw_mem.len() > MEMORY_LIMIT {
             return Err(Fault::ProgramTooBig(raw_mem.len()));
         }
 
-        let mut memory: [Option<isize>; MEMORY_SIZE] = [None; MEMORY_SIZE];
-        memory[..raw_mem.len()].copy_from_slice(&raw_mem);
+        Ok(IntCodeComputer::new(raw_mem))
+    }
+}
 
-        Ok(IntCodeComputer::new(memory))
+/// Parses the values found between a pair of commas in a program. There is usually only one, but
+/// any whitespace inside the chunk separates additional values. A chunk without any value in it at
+/// all is an error. The provided index is the position of the first value in the program, which
+/// is used to report where any invalid token was found.
+fn parse_chunk(chunk: &str, first_index: usize) -> Result<Vec<isize>, Fault> {
+    let chunk = chunk.trim();
+    if chunk.is_empty() {
+        return Err(Fault::InvalidToken(first_index, String::new()));
     }
+
+    chunk
+        .split_whitespace()
+        .enumerate()
+        .map(|(offset, token)| {
+            token
+                .parse::<isize>()
+                .map_err(|_| Fault::InvalidToken(first_index + offset, token.to_string()))
+        })
+        .collect()
+}
+
+/// The most common way to use the computer is to run a program once with some input and look at
+/// what it output. This does exactly that with a fresh machine, without needing to hold onto it.
+/// A program that stops to wait for more input than was provided is returned the output it
+/// produced up to that point.
+pub fn execute(program: &str, input: Vec<isize>) -> Result<Vec<isize>, Fault> {
+    let mut icc = IntCodeComputer::from_str(program)?.with_input(input);
+    icc.run()?;
+
+    Ok(icc.output())
+}
+
+/// Brute forces every noun (address 1) and verb (address 2) between 0 and 99 looking for the
+/// combinations that leave the target value at address 0 once the program halts. Usually there is
+/// only one, but collecting all of them makes it possible to confirm that. Combinations that cause
+/// the program to fault can't produce the target and are skipped, only a program that fails to
+/// parse is reported as an error.
+pub fn find_all_noun_verb(program: &str, target: isize) -> Result<Vec<(isize, isize)>, Fault> {
+    IntCodeComputer::from_str(program)?;
+    Ok(noun_verb_matches(program, target).collect())
+}
+
+/// Performs the same search as `find_all_noun_verb()` but stops at the first combination that
+/// produces the target, checking nouns and then verbs in ascending order. The puzzle promises a
+/// single answer so there isn't any need to keep looking once it turns up.
+pub fn find_noun_verb(program: &str, target: isize) -> Result<Option<(isize, isize)>, Fault> {
+    IntCodeComputer::from_str(program)?;
+    Ok(noun_verb_matches(program, target).next())
+}
+
+/// Lazily works through each noun and verb combination in ascending order, yielding the ones that
+/// leave the target at address 0. Any fault is treated as a miss, including the program failing
+/// to parse, so that needs to be checked before searching.
+fn noun_verb_matches(program: &str, target: isize) -> impl Iterator<Item = (isize, isize)> + '_ {
+    (0..100)
+        .flat_map(|noun| (0..100).map(move |verb| (noun, verb)))
+        .filter(move |&(noun, verb)| {
+            run_program_for_addr0(program, &[(1, noun), (2, verb)]) == Ok(target)
+        })
+}
+
+/// Reads a program out of the file at the provided path. The surrounding whitespace, such as the
+/// newline at the end of the puzzle input, is trimmed off so the result can be used directly.
+pub fn load_program(path: &str) -> io::Result<String> {
+    Ok(fs::read_to_string(path)?.trim().to_string())
+}
+
+/// A lot of programs only communicate their result through the value left at address 0 once they
+/// have halted. This parses the program, stores each of the `(address, value)` patches into memory,
+/// runs it to completion and returns that final value.
+pub fn run_program_for_addr0(program: &str, patches: &[(usize, isize)]) -> Result<isize, Fault> {
+    let mut icc = IntCodeComputer::from_str(program)?;
+
+    for (address, value) in patches {
+        let address: isize = match (*address).try_into() {
+            Ok(addr) => addr,
+            Err(_) => return Err(Fault::MemoryExceeded),
+        };
+
+        icc.store(address, *value)?;
+    }
+
+    icc.run()?;
+    icc.mem_read(0)
 }
 
 /// This specifies the valid instruction set for the IntCodeComputer as defined by the 2019 Advent
@@ -416,31 +1490,85 @@ impl FromStr for IntCodeComputer {
 pub enum Operation {
     Add(usize),
     Mul(usize),
-    Input,
+    Input(usize),
     Output(usize),
     JumpIfTrue(usize),
     JumpIfFalse(usize),
     LessThan(usize),
     Equals(usize),
+
+    /// Added in day 9, this adjusts the relative base used by relative mode parameters by the
+    /// value of its only parameter.
+    AdjustRelativeBase(usize),
     Halt,
+
+    /// A non-standard extension that halts like `Halt` but also provides an exit code through its
+    /// single parameter. This is only decoded when extensions have been enabled on the machine.
+    HaltWithCode(usize),
 }
 
 impl Operation {
-    /// Instructions have varying widths. This returns the amount of memory they take up so they
-    /// can be appropriately jumped over to the next instruction.
-    pub fn instruction_size(&self) -> usize {
+    /// Whether the operation may move the program counter somewhere other than the next
+    /// instruction.
+    pub fn is_jump(&self) -> bool {
+        matches!(self, Self::JumpIfTrue(_) | Self::JumpIfFalse(_))
+    }
+
+    /// Whether the operation communicates with the outside world through the input or output
+    /// buffers.
+    pub fn is_io(&self) -> bool {
+        matches!(self, Self::Input(_) | Self::Output(_))
+    }
+
+    /// The number of parameters that follow the operation in memory. Jumps take their target as a
+    /// parameter just like any other so they're no exception to this.
+    pub fn arity(&self) -> usize {
+        match *self {
+            Self::Add(_) => 3,
+            Self::Mul(_) => 3,
+            Self::Input(_) => 1,
+            Self::Output(_) => 1,
+            Self::JumpIfTrue(_) => 2,
+            Self::JumpIfFalse(_) => 2,
+            Self::LessThan(_) => 3,
+            Self::Equals(_) => 3,
+            Self::AdjustRelativeBase(_) => 1,
+            Self::Halt => 0,
+            Self::HaltWithCode(_) => 1,
+        }
+    }
+
+    /// The short name used for the operation when disassembling a program.
+    pub fn mnemonic(&self) -> &'static str {
         match *self {
-            Self::Add(_) => 4,
-            Self::Mul(_) => 4,
-            Self::Input => 2,
-            Self::Output(_) => 2,
-            Self::JumpIfTrue(_) => 3,
-            Self::JumpIfFalse(_) => 3,
-            Self::LessThan(_) => 4,
-            Self::Equals(_) => 4,
-            Self::Halt => 1,
+            Self::Add(_) => "ADD",
+            Self::Mul(_) => "MUL",
+            Self::Input(_) => "IN",
+            Self::Output(_) => "OUT",
+            Self::JumpIfTrue(_) => "JT",
+            Self::JumpIfFalse(_) => "JF",
+            Self::LessThan(_) => "LT",
+            Self::Equals(_) => "EQ",
+            Self::AdjustRelativeBase(_) => "ARB",
+            Self::Halt => "HALT",
+            Self::HaltWithCode(_) => "EXIT",
         }
     }
+
+    /// Instructions have varying widths. This returns the amount of memory they take up so they
+    /// can be appropriately jumped over to the next instruction, which is the operation itself
+    /// followed by each of its parameters.
+    pub fn instruction_size(&self) -> usize {
+        1 + self.arity()
+    }
+
+    /// Whether the operation stores a value into memory when it is executed.
+    pub fn writes_memory(&self) -> bool {
+        matches!(
+            self,
+            Self::Add(_) | Self::Mul(_) | Self::Input(_) | Self::LessThan(_) | Self::Equals(_)
+        )
+    }
 }
 
 #[cfg(test)]
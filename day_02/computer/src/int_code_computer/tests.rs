@@ -1,11 +1,137 @@
+use std::io::Cursor;
+
 use super::*;
 
 type FaultResult = Result<(), Fault>;
 
+/// A minimal port for testing that hands out its input in order and collects everything written
+/// to it.
+struct VecPort {
+    input: Vec<isize>,
+    output: Vec<isize>,
+}
+
+impl IoPort for VecPort {
+    fn read(&mut self) -> Option<isize> {
+        if self.input.is_empty() {
+            None
+        } else {
+            Some(self.input.remove(0))
+        }
+    }
+
+    fn write(&mut self, value: isize) {
+        self.output.push(value);
+    }
+}
+
 fn init_logger() {
     let _ = env_logger::builder().is_test(true).try_init();
 }
 
+#[test]
+fn test_fault_codes() {
+    init_logger();
+
+    let faults = vec![
+        Fault::EmptyJournal,
+        Fault::InvalidProgramCount(14, -3),
+        Fault::InvalidToken(3, "x:y".to_string()),
+        Fault::IoError("value `x` isn't a valid number: bad: really".to_string()),
+        Fault::MemoryExceeded,
+        Fault::MisalignedJump(7),
+        Fault::OutputOverflow,
+        Fault::NegativeMemoryAddress(8, -12),
+        Fault::ParameterModeInvalid(22),
+        Fault::ProgramTooBig(2048),
+        Fault::StepLimitExceeded(500),
+        Fault::UninitializedOperation(31),
+        Fault::UnknownOperation(10, 7500),
+    ];
+
+    for fault in faults {
+        let code = fault.to_code();
+        assert_eq!(Fault::from_code(&code), Some(fault), "{}", code);
+    }
+
+    assert_eq!(
+        Fault::UnknownOperation(10, 7500).to_code(),
+        "UnknownOperation:10:7500"
+    );
+    assert_eq!(
+        Fault::UnknownOperation(10, 7500).to_string(),
+        "unknown operation 7500 at 10"
+    );
+
+    // Unknown names, missing values, and extra values are all rejected
+    assert_eq!(Fault::from_code("NotAFault"), None);
+    assert_eq!(Fault::from_code("UnknownOperation:10"), None);
+    assert_eq!(Fault::from_code("MemoryExceeded:1"), None);
+    assert_eq!(Fault::from_code("MisalignedJump:-1"), None);
+}
+
+#[test]
+fn test_fault_as_error() {
+    fn faulting_main() -> Result<(), Box<dyn std::error::Error>> {
+        let mut icc = IntCodeComputer::from_str("1,0,0,0,99")?;
+        icc.jump_to(5)?;
+        icc.run()?;
+
+        Ok(())
+    }
+
+    let err = faulting_main().unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "attempted to execute uninitialized memory at 5"
+    );
+    assert!(err.source().is_none());
+
+    assert_eq!(
+        Fault::NegativeMemoryAddress(9, -3).to_string(),
+        "instruction at 9 accessed negative address -3"
+    );
+    assert_eq!(
+        Fault::InvalidProgramCount(4, -1).to_string(),
+        "instruction at 4 attempted to jump to invalid address -1"
+    );
+}
+
+#[test]
+fn test_invalid_tokens() -> FaultResult {
+    init_logger();
+
+    assert!(matches!(
+        IntCodeComputer::from_str("1,2,x,4"),
+        Err(Fault::InvalidToken(2, token)) if token == "x"
+    ));
+
+    // A trailing comma leaves an empty value at the end
+    assert!(matches!(
+        IntCodeComputer::from_str("1,0,0,0,99,"),
+        Err(Fault::InvalidToken(5, token)) if token.is_empty()
+    ));
+
+    assert_eq!(
+        Fault::InvalidToken(2, "x".to_string()).to_string(),
+        "value `x` at position 2 isn't a valid number"
+    );
+
+    // Valid programs, including surrounding whitespace, are still fine
+    let ic = IntCodeComputer::from_str(" 1,0,0,0,99\n")?;
+    assert_eq!(ic.memory_str(), "1,0,0,0,99");
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_size() {
+    init_logger();
+
+    // This is the single definition every day links against through the crate level export
+    assert_eq!(crate::MEMORY_SIZE, 1024);
+}
+
 #[test]
 fn test_advancing() -> FaultResult {
     init_logger();
@@ -20,19 +146,13 @@ fn test_advancing() -> FaultResult {
     assert_eq!(ic.program_counter(), 7);
 
     let mut ic = IntCodeComputer {
-        pc: MEMORY_SIZE - 1,
-
-        input: Vec::new(),
-        memory: [None; MEMORY_SIZE],
-        output: Vec::new(),
-
-        waiting_on_input: false,
-        original_memory: [None; MEMORY_SIZE],
+        pc: MEMORY_LIMIT - 1,
+        ..IntCodeComputer::default()
     };
 
-    // Allow advancing to equal to the memory size (allow halt to be the final instruction)
+    // Allow advancing to equal to the memory limit (allow halt to be the final instruction)
     ic.advance(1)?;
-    assert_eq!(ic.program_counter(), MEMORY_SIZE);
+    assert_eq!(ic.program_counter(), MEMORY_LIMIT);
 
     // Ensure we can't advance any further without triggering an error
     assert_eq!(ic.advance(1), Err(Fault::MemoryExceeded));
@@ -49,11 +169,14 @@ fn test_memory_retrieval() -> FaultResult {
     ic.store(7, 45)?;
     assert_eq!(ic.mem_read(7)?, 45);
 
-    assert_eq!(ic.mem_read(1), Err(Fault::MissingMemory(0, 1)));
+    // Memory outside of the program reads as zero
+    assert_eq!(ic.mem_read(1)?, 0);
+    assert_eq!(ic.mem_read((MEMORY_SIZE + 1).try_into().unwrap())?, 0);
     assert_eq!(
-        ic.mem_read((MEMORY_SIZE + 1).try_into().unwrap()),
+        ic.mem_read(MEMORY_LIMIT.try_into().unwrap()),
         Err(Fault::MemoryExceeded)
     );
+    assert_eq!(ic.mem_read(-1), Err(Fault::NegativeMemoryAddress(0, -1)));
 
     Ok(())
 }
@@ -68,9 +191,169 @@ fn test_memory_storage() -> FaultResult {
     assert_eq!(ic.mem_read(0)?, 100);
 
     assert_eq!(
-        ic.store((MEMORY_SIZE + 1).try_into().unwrap(), 6000),
+        ic.store(MEMORY_LIMIT.try_into().unwrap(), 6000),
         Err(Fault::MemoryExceeded)
     );
+    assert_eq!(ic.store(-4, 6000), Err(Fault::NegativeMemoryAddress(0, -4)));
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_growth() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::from_str("1,0,0,0,99")?;
+
+    // Storing well past the initial memory grows it to fit
+    ic.store(5000, 1234)?;
+    assert_eq!(ic.mem_read(5000)?, 1234);
+    assert_eq!(ic.mem_read(4999)?, 0);
+    assert_eq!(ic.memory_str(), "1,0,0,0,99,1234");
+
+    // Resetting puts the memory back to the way the program started
+    ic.reset();
+    assert_eq!(ic.mem_read(5000)?, 0);
+    assert_eq!(ic.memory_str(), "1,0,0,0,99");
+
+    // Programs are able to grow memory themselves as well
+    assert_eq!(execute("1101,3,4,5000,4,5000,99", vec![])?, vec![7]);
+
+    Ok(())
+}
+
+#[test]
+fn test_disassemble() -> FaultResult {
+    init_logger();
+
+    // The multiplication uses an immediate parameter, and the value it modifies isn't a valid
+    // operation until the program has run
+    let ic = IntCodeComputer::from_str("1002,4,3,4,33")?;
+    assert_eq!(
+        ic.disassemble(),
+        vec!["0000: MUL [4] #3 -> [4]", "0004: DATA 33"]
+    );
+
+    // Everything after the first halt is data and isn't rendered
+    let ic = IntCodeComputer::from_str("109,-3,21101,6,7,13,3,9,204,2,99,1,2")?;
+    assert_eq!(
+        ic.disassemble(),
+        vec![
+            "0000: ARB #-3",
+            "0002: ADD #6 #7 -> [rb+13]",
+            "0006: IN -> [9]",
+            "0008: OUT [rb+2]",
+            "0010: HALT",
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_dump() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::from_str("1,0,0,0,99")?;
+    ic.store(7, -12)?;
+
+    assert_eq!(
+        ic.dump(3, 9),
+        "3: 0\n4: 99\n5: uninit\n6: uninit\n7: -12\n8: uninit\n"
+    );
+    assert_eq!(ic.dump(4, 4), "");
+
+    // Ranges are clipped to the end of memory
+    let last = format!("{}: uninit\n", MEMORY_SIZE - 1);
+    assert_eq!(ic.dump(MEMORY_SIZE - 1, MEMORY_SIZE + 10), last);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_map() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::default();
+    assert_eq!(ic.memory_map(), vec![]);
+
+    ic.store(0, 1)?;
+    ic.store(2, 3)?;
+
+    // The gap is collapsed in the string form but addresses are preserved in the map
+    assert_eq!(ic.memory_str(), "1,3");
+    assert_eq!(ic.memory_map(), vec![(0, 1), (2, 3)]);
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_equality() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+
+    // Running straight through and stepping one instruction at a time should end up the same
+    let mut baseline = IntCodeComputer::from_str(sample_prog)?;
+    baseline.run()?;
+
+    let mut stepped = IntCodeComputer::from_str(sample_prog)?;
+    stepped.enable_journaling(true);
+    while !stepped.is_halted() {
+        stepped.step()?;
+    }
+    assert!(stepped.memory_equals(&baseline));
+    assert!(baseline.memory_equals(&stepped));
+
+    // Growing memory without writing anything into the new space doesn't matter
+    let mut grown = stepped.clone();
+    grown.memory.resize(MEMORY_SIZE * 2, None);
+    assert!(grown.memory_equals(&baseline));
+
+    let fresh = IntCodeComputer::from_str(sample_prog)?;
+    assert!(!fresh.memory_equals(&baseline));
+
+    // The same values at different addresses aren't equal even though they print the same
+    let mut first = IntCodeComputer::default();
+    first.store(0, 1)?;
+    first.store(2, 3)?;
+
+    let mut second = IntCodeComputer::default();
+    second.store(0, 1)?;
+    second.store(1, 3)?;
+
+    assert_eq!(first.memory_str(), second.memory_str());
+    assert!(!first.memory_equals(&second));
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_stats() -> FaultResult {
+    init_logger();
+
+    let ic = IntCodeComputer::default();
+    assert_eq!(
+        ic.memory_stats(),
+        MemoryStats {
+            min: None,
+            max: None,
+            initialized: 0,
+            negative: 0,
+        }
+    );
+
+    let mut ic = IntCodeComputer::from_str("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9")?;
+    ic.store(100, -5000)?;
+    assert_eq!(
+        ic.memory_stats(),
+        MemoryStats {
+            min: Some(-5000),
+            max: Some(99),
+            initialized: 17,
+            negative: 2,
+        }
+    );
 
     Ok(())
 }
@@ -121,7 +404,7 @@ fn test_op_parsing() -> FaultResult {
     assert_eq!(ic.current_op()?, Operation::Mul(0));
 
     ic.advance(1)?;
-    assert_eq!(ic.current_op()?, Operation::Input);
+    assert_eq!(ic.current_op()?, Operation::Input(0));
 
     ic.advance(1)?;
     assert_eq!(ic.current_op()?, Operation::Output(0));
@@ -150,6 +433,57 @@ fn test_op_parsing() -> FaultResult {
     Ok(())
 }
 
+#[test]
+fn test_op_classification() {
+    init_logger();
+
+    // Each case is the operation followed by whether it's a jump, io, and writes memory
+    let cases = vec![
+        (Operation::Add(0), false, false, true),
+        (Operation::Mul(0), false, false, true),
+        (Operation::Input(0), false, true, true),
+        (Operation::Output(0), false, true, false),
+        (Operation::JumpIfTrue(0), true, false, false),
+        (Operation::JumpIfFalse(0), true, false, false),
+        (Operation::LessThan(0), false, false, true),
+        (Operation::Equals(0), false, false, true),
+        (Operation::AdjustRelativeBase(0), false, false, false),
+        (Operation::Halt, false, false, false),
+        (Operation::HaltWithCode(0), false, false, false),
+    ];
+
+    for (op, jump, io, writes) in cases {
+        assert_eq!(op.is_jump(), jump, "{:?}", op);
+        assert_eq!(op.is_io(), io, "{:?}", op);
+        assert_eq!(op.writes_memory(), writes, "{:?}", op);
+    }
+}
+
+#[test]
+fn test_op_arity() {
+    init_logger();
+
+    let cases = vec![
+        (Operation::Add(0), 3, 4),
+        (Operation::Mul(0), 3, 4),
+        (Operation::Input(0), 1, 2),
+        (Operation::Output(0), 1, 2),
+        (Operation::JumpIfTrue(0), 2, 3),
+        (Operation::JumpIfFalse(0), 2, 3),
+        (Operation::LessThan(0), 3, 4),
+        (Operation::Equals(0), 3, 4),
+        (Operation::AdjustRelativeBase(0), 1, 2),
+        (Operation::Halt, 0, 1),
+        (Operation::HaltWithCode(0), 1, 2),
+    ];
+
+    for (op, arity, size) in cases {
+        assert_eq!(op.arity(), arity, "{:?}", op);
+        assert_eq!(op.instruction_size(), size, "{:?}", op);
+        assert_eq!(op.instruction_size(), op.arity() + 1, "{:?}", op);
+    }
+}
+
 #[test]
 fn test_prog_parsing() {
     init_logger();
@@ -160,6 +494,98 @@ fn test_prog_parsing() {
     assert_eq!(ic.memory_str(), sample_prog);
 }
 
+#[test]
+fn test_reader_parsing() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50\n";
+
+    let from_reader = IntCodeComputer::from_reader(Cursor::new(sample_prog))?;
+    let from_str = IntCodeComputer::from_str(sample_prog)?;
+    assert!(from_reader.memory == from_str.memory);
+    assert_eq!(from_reader.memory_str(), "1,9,10,3,2,3,11,0,99,30,40,50");
+
+    assert!(matches!(
+        IntCodeComputer::from_reader(Cursor::new("1,x,3")),
+        Err(Fault::InvalidToken(1, token)) if token == "x"
+    ));
+
+    let oversized = vec!["99"; MEMORY_LIMIT + 2].join(",");
+    assert!(matches!(
+        IntCodeComputer::from_reader(Cursor::new(oversized)),
+        Err(Fault::ProgramTooBig(count)) if count == MEMORY_LIMIT + 2
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_reader_matches_str() {
+    init_logger();
+
+    let parse_both = |prog: &str| {
+        (
+            IntCodeComputer::from_str(prog).map(|ic| ic.memory),
+            IntCodeComputer::from_reader(Cursor::new(prog)).map(|ic| ic.memory),
+        )
+    };
+
+    for prog in &["1,0,0,0,99", "1,0,0,0,99\n", " 1 0,0,\n0,99 ", "42"] {
+        let (from_str, from_reader) = parse_both(prog);
+        assert!(from_str.is_ok(), "{:?}", prog);
+        assert!(from_str == from_reader, "{:?}", prog);
+    }
+
+    for prog in &[
+        "",
+        "\n",
+        "1,0,0,0,99,",
+        "1,0,0,0,99,\n",
+        ",1",
+        "1,,2",
+        "1,x",
+    ] {
+        let (from_str, from_reader) = parse_both(prog);
+        assert!(from_str.is_err(), "{:?}", prog);
+        assert_eq!(from_str.err(), from_reader.err(), "{:?}", prog);
+    }
+}
+
+#[test]
+fn test_slice_construction() -> FaultResult {
+    init_logger();
+
+    let mut icc = IntCodeComputer::from_slice(&[1, 0, 0, 0, 99])?;
+    icc.run()?;
+    assert_eq!(icc.memory_str(), "2,0,0,0,99");
+
+    let from_str = IntCodeComputer::from_str("1,9,10,3,2,3,11,0,99,30,40,50")?;
+    let from_slice = IntCodeComputer::from_slice(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50])?;
+    assert!(from_slice.memory == from_str.memory);
+
+    assert!(matches!(
+        IntCodeComputer::from_slice(&vec![99; MEMORY_LIMIT + 1]),
+        Err(Fault::ProgramTooBig(count)) if count == MEMORY_LIMIT + 1
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_load_program() -> Result<(), io::Error> {
+    init_logger();
+
+    let path = std::env::temp_dir().join(format!("computer_{}.txt", std::process::id()));
+    fs::write(&path, "1,0,0,0,99\n")?;
+    let program = load_program(path.to_str().unwrap());
+    fs::remove_file(&path)?;
+
+    assert_eq!(program?, "1,0,0,0,99");
+    assert!(load_program("./does/not/exist.txt").is_err());
+
+    Ok(())
+}
+
 #[test]
 fn test_trailing_whitespace() {
     init_logger();
@@ -170,6 +596,48 @@ fn test_trailing_whitespace() {
     assert_eq!(ic.memory_str(), "1,2,3,100,0");
 }
 
+#[test]
+fn test_whitespace_separated() -> FaultResult {
+    init_logger();
+
+    let expected = IntCodeComputer::from_str("1,0,0,0,99")?;
+
+    for prog in &[
+        "1 0 0 0 99",
+        "1,\n0,0,0,99",
+        "1\n0\n0\n0\n99\n",
+        " 1, 0 0,\t0 ,99 ",
+    ] {
+        let ic = IntCodeComputer::from_str(prog)?;
+        assert!(ic.memory == expected.memory, "{:?}", prog);
+
+        let from_reader = IntCodeComputer::from_reader(Cursor::new(prog))?;
+        assert!(from_reader.memory == expected.memory, "{:?}", prog);
+    }
+
+    // Positions still count every value, not just those separated by commas
+    assert!(matches!(
+        IntCodeComputer::from_str("1 0 0,x 99"),
+        Err(Fault::InvalidToken(3, token)) if token == "x"
+    ));
+    assert!(matches!(
+        IntCodeComputer::from_str("1 0,0 0 y"),
+        Err(Fault::InvalidToken(4, token)) if token == "y"
+    ));
+
+    // Whitespace doesn't make up for a missing value between commas
+    assert!(matches!(
+        IntCodeComputer::from_str("1 0,,0,0,99"),
+        Err(Fault::InvalidToken(2, token)) if token.is_empty()
+    ));
+    assert!(matches!(
+        IntCodeComputer::from_reader(Cursor::new("1,0, ,0,99")),
+        Err(Fault::InvalidToken(2, token)) if token.is_empty()
+    ));
+
+    Ok(())
+}
+
 #[test]
 fn test_addition_step() -> FaultResult {
     init_logger();
@@ -213,7 +681,7 @@ fn test_input_step() -> FaultResult {
     ic.add_input(vec![-832]);
     assert_eq!(ic.memory_str(), sample_prog);
 
-    assert_eq!(ic.current_op()?, Operation::Input);
+    assert_eq!(ic.current_op()?, Operation::Input(0));
     ic.step()?;
     assert_eq!(ic.program_counter(), 2);
     assert_eq!(ic.memory_str(), "3,3,99,-832");
@@ -291,6 +759,31 @@ fn test_jump_if_false_step() -> FaultResult {
     Ok(())
 }
 
+#[test]
+fn test_strict_jumps() -> FaultResult {
+    init_logger();
+
+    // The jump lands on the second parameter of the Add instruction which happens to decode as a
+    // halt
+    let sample_prog = "1105,1,5,1101,4,99,10,99";
+
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.run()?;
+    assert_eq!(ic.program_counter(), 5);
+
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.set_strict_jumps(true);
+    assert_eq!(ic.run(), Err(Fault::MisalignedJump(5)));
+
+    // Jumps to the start of an instruction are still allowed in strict mode
+    let mut ic = IntCodeComputer::from_str("1105,1,7,1101,4,99,10,99")?;
+    ic.set_strict_jumps(true);
+    ic.run()?;
+    assert_eq!(ic.program_counter(), 7);
+
+    Ok(())
+}
+
 #[test]
 fn test_less_than_step() -> FaultResult {
     init_logger();
@@ -357,67 +850,263 @@ fn test_halt_step() -> FaultResult {
     assert_eq!(ic.current_op()?, Operation::Halt);
     ic.step()?;
     assert_eq!(ic.memory_str(), "99");
-    assert_eq!(ic.program_counter(), 1);
+    assert_eq!(ic.program_counter(), 0);
 
     Ok(())
 }
 
-// This is the test program walked through by the advent challenge
 #[test]
-fn test_stepping_sample_prog() -> FaultResult {
+fn test_stepping_while_halted() -> FaultResult {
     init_logger();
 
-    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    // The data after the halt would be a valid instruction if the machine walked past the halt
+    let sample_prog = "104,7,99,104,8,99";
     let mut ic = IntCodeComputer::from_str(sample_prog)?;
 
-    ic.step()?;
-    assert_eq!(ic.memory_str(), "1,9,10,70,2,3,11,0,99,30,40,50");
-    assert_eq!(ic.program_counter(), 4);
+    ic.run()?;
+    assert!(ic.is_halted());
+    assert_eq!(ic.program_counter(), 2);
+    assert_eq!(ic.output(), vec![7]);
 
-    ic.step()?;
-    assert_eq!(ic.memory_str(), "3500,9,10,70,2,3,11,0,99,30,40,50");
-    assert_eq!(ic.program_counter(), 8);
+    for _ in 0..3 {
+        ic.step()?;
+        assert!(ic.is_halted());
+        assert_eq!(ic.program_counter(), 2);
+    }
 
-    // This is the halt instruction and should also complete successfully, termination of
-    // execution is tested via the run() function.
-    ic.step()?;
+    ic.run()?;
+    assert_eq!(ic.program_counter(), 2);
+    assert_eq!(ic.memory_str(), sample_prog);
+    assert_eq!(ic.output(), vec![]);
 
     Ok(())
 }
 
-// Test the same program but rather than stepping just run it
 #[test]
-fn test_running_sample_prog() -> FaultResult {
+fn test_halt_with_exit_code() -> FaultResult {
     init_logger();
 
-    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    // Reads a value, adds 5 to it and exits with the result as the exit code
+    let sample_prog = "3,8,1001,8,5,8,97,8,0";
+
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.set_allow_extensions(true);
+    ic.add_input(vec![37]);
+    ic.run()?;
+
+    assert!(ic.is_halted());
+    assert_eq!(ic.current_op()?, Operation::HaltWithCode(0));
+    assert_eq!(ic.exit_code(), Some(42));
+
+    // Without extensions enabled this is just an unknown operation
     let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.add_input(vec![37]);
+    assert_eq!(ic.run(), Err(Fault::UnknownOperation(6, 97)));
+    assert_eq!(ic.exit_code(), None);
 
+    // Normal halts don't have an exit code
+    let mut ic = IntCodeComputer::from_str("99")?;
+    ic.set_allow_extensions(true);
     ic.run()?;
-    assert_eq!(ic.memory_str(), "3500,9,10,70,2,3,11,0,99,30,40,50");
+    assert_eq!(ic.exit_code(), None);
 
     Ok(())
 }
 
 #[test]
-fn test_additional_progs() -> FaultResult {
+fn test_stepping_back() -> FaultResult {
     init_logger();
 
-    let programs: [(&'static str, &'static str); 4] = [
-        ("1,0,0,0,99", "2,0,0,0,99"),
-        ("2,3,0,3,99", "2,3,0,6,99"),
-        ("2,4,4,5,99,0", "2,4,4,5,99,9801"),
-        ("1,1,1,4,99,5,6,0,99", "30,1,1,4,2,5,6,0,99"),
-    ];
+    let mut ic = IntCodeComputer::from_str("3,0,1002,0,3,0,4,0,99")?;
+    ic.enable_journaling(true);
+    ic.add_input(vec![7, 8]);
 
-    for (prog, result) in programs.iter() {
-        let mut ic = IntCodeComputer::from_str(prog)?;
-        ic.run()?;
-        assert_eq!(ic.memory_str(), result.to_string());
-    }
+    // Nothing has been recorded yet
+    assert_eq!(ic.step_back(), Err(Fault::EmptyJournal));
 
-    Ok(())
-}
+    ic.step()?;
+    assert_eq!(ic.program_counter(), 2);
+    assert_eq!(ic.mem_read(0)?, 7);
+
+    ic.step()?;
+    assert_eq!(ic.program_counter(), 6);
+    assert_eq!(ic.mem_read(0)?, 21);
+
+    // Undo the multiplication, we should be sitting right after the input again
+    ic.step_back()?;
+    assert_eq!(ic.program_counter(), 2);
+    assert_eq!(ic.mem_read(0)?, 7);
+
+    // Undo the input as well, this should put the 7 back at the front of the queue
+    ic.step_back()?;
+    assert_eq!(ic.program_counter(), 0);
+    assert_eq!(ic.mem_read(0)?, 3);
+    assert_eq!(ic.step_back(), Err(Fault::EmptyJournal));
+
+    // Running forward again should reproduce the original behavior
+    ic.run()?;
+    assert_eq!(ic.output(), vec![21]);
+
+    // Stepping while halted isn't recorded so stepping back undoes the output instruction
+    ic.step()?;
+    ic.step_back()?;
+    assert_eq!(ic.program_counter(), 6);
+    ic.step()?;
+    ic.step_back()?;
+    assert_eq!(ic.output(), vec![]);
+
+    ic.step_back()?;
+    ic.step()?;
+    ic.step()?;
+    assert_eq!(ic.output(), vec![21]);
+
+    // Disabling journaling drops everything that was recorded
+    ic.enable_journaling(false);
+    assert_eq!(ic.step_back(), Err(Fault::EmptyJournal));
+
+    // Blocking on input doesn't do anything worth undoing, stepping back from there reverts the
+    // instruction that came before it
+    let mut ic = IntCodeComputer::from_str("1101,2,3,7,3,7,99,0")?;
+    ic.enable_journaling(true);
+    ic.step()?;
+    ic.step()?;
+    assert!(ic.is_waiting_on_input());
+    assert_eq!(ic.mem_read(7)?, 5);
+
+    ic.step_back()?;
+    assert_eq!(ic.program_counter(), 0);
+    assert_eq!(ic.mem_read(7)?, 0);
+    assert_eq!(ic.step_back(), Err(Fault::EmptyJournal));
+
+    Ok(())
+}
+
+// This is the test program walked through by the advent challenge
+#[test]
+fn test_stepping_sample_prog() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+
+    ic.step()?;
+    assert_eq!(ic.memory_str(), "1,9,10,70,2,3,11,0,99,30,40,50");
+    assert_eq!(ic.program_counter(), 4);
+
+    ic.step()?;
+    assert_eq!(ic.memory_str(), "3500,9,10,70,2,3,11,0,99,30,40,50");
+    assert_eq!(ic.program_counter(), 8);
+
+    // This is the halt instruction and should also complete successfully, termination of
+    // execution is tested via the run() function.
+    ic.step()?;
+
+    Ok(())
+}
+
+// Test the same program but rather than stepping just run it
+#[test]
+fn test_running_sample_prog() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+
+    ic.run()?;
+    assert_eq!(ic.memory_str(), "3500,9,10,70,2,3,11,0,99,30,40,50");
+
+    Ok(())
+}
+
+#[test]
+fn test_running_for_addr0() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+
+    assert_eq!(run_program_for_addr0(sample_prog, &[])?, 3500);
+    assert_eq!(run_program_for_addr0(sample_prog, &[(1, 9), (2, 9)])?, 3000);
+    assert_eq!(
+        run_program_for_addr0(sample_prog, &[(1, 11), (2, 10)])?,
+        4500
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_executing() -> FaultResult {
+    init_logger();
+
+    assert_eq!(execute("3,0,4,0,99", vec![42])?, vec![42]);
+    assert_eq!(execute("3,0,4,0,99", vec![-7])?, vec![-7]);
+    assert_eq!(execute("1,0,0,0,99", vec![])?, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_finding_all_noun_verbs() -> FaultResult {
+    init_logger();
+
+    // Multiplies the noun and verb together in immediate mode, as 97 is prime there are exactly
+    // two ways to produce it
+    let sample_prog = "1102,0,0,0,99";
+    assert_eq!(find_all_noun_verb(sample_prog, 97)?, vec![(1, 97), (97, 1)]);
+    assert_eq!(find_all_noun_verb(sample_prog, 9_802)?, vec![]);
+
+    // Memory beyond the program reads as zero, which opens up a couple of less obvious solutions
+    // that pair a value with an empty cell
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    assert_eq!(
+        find_all_noun_verb(sample_prog, 3_500)?,
+        vec![(2, 70), (9, 10), (10, 9), (70, 1)]
+    );
+
+    // Combinations that fault should be skipped rather than failing the whole search. Every
+    // combination here writes to a negative address, even though address 0 is left untouched
+    let sample_prog = "1101,0,0,-1,99";
+    assert_eq!(find_all_noun_verb(sample_prog, 1_101)?, vec![]);
+
+    Ok(())
+}
+
+#[test]
+fn test_finding_noun_verb() -> FaultResult {
+    init_logger();
+
+    // The same multiplication as above, the noun is searched first so the smaller one wins
+    let sample_prog = "1102,0,0,0,99";
+    assert_eq!(find_noun_verb(sample_prog, 97)?, Some((1, 97)));
+    assert_eq!(find_noun_verb(sample_prog, 9_802)?, None);
+
+    // Adds the values at the noun and verb addresses, then multiplies by the value at 11. The
+    // empty memory past the end of the program turns up an answer before the obvious (9, 10)
+    let sample_prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    assert_eq!(find_noun_verb(sample_prog, 3_500)?, Some((2, 70)));
+
+    Ok(())
+}
+
+#[test]
+fn test_additional_progs() -> FaultResult {
+    init_logger();
+
+    let programs: [(&'static str, &'static str); 4] = [
+        ("1,0,0,0,99", "2,0,0,0,99"),
+        ("2,3,0,3,99", "2,3,0,6,99"),
+        ("2,4,4,5,99,0", "2,4,4,5,99,9801"),
+        ("1,1,1,4,99,5,6,0,99", "30,1,1,4,2,5,6,0,99"),
+    ];
+
+    for (prog, result) in programs.iter() {
+        let mut ic = IntCodeComputer::from_str(prog)?;
+        ic.run()?;
+        assert_eq!(ic.memory_str(), result.to_string());
+    }
+
+    Ok(())
+}
 
 #[test]
 fn test_input_output_program() -> FaultResult {
@@ -433,6 +1122,117 @@ fn test_input_output_program() -> FaultResult {
     Ok(())
 }
 
+#[test]
+fn test_chained_input() -> FaultResult {
+    init_logger();
+
+    let sample_prog = "3,9,8,9,10,9,4,9,99,-1,8";
+
+    let mut separate = IntCodeComputer::from_str(sample_prog)?;
+    separate.add_input(vec![8]);
+    separate.run()?;
+
+    let mut chained = IntCodeComputer::from_str(sample_prog)?;
+    chained.feed(vec![8]).run()?;
+    assert_eq!(chained.output(), separate.output());
+
+    let mut built = IntCodeComputer::from_str(sample_prog)?.with_input(vec![4]);
+    built.run()?;
+    assert_eq!(built.output(), vec![0]);
+
+    Ok(())
+}
+
+#[test]
+fn test_piping_output() -> FaultResult {
+    init_logger();
+
+    let mut first = IntCodeComputer::from_str("104,1,104,2,104,3,99")?;
+    let mut second = IntCodeComputer::from_str("3,13,4,13,3,13,4,13,3,13,4,13,99")?;
+
+    first.run()?;
+    first.pipe_output_into(&mut second);
+    assert_eq!(first.output(), vec![]);
+
+    second.run()?;
+    assert!(second.is_halted());
+    assert_eq!(second.output(), vec![1, 2, 3]);
+
+    Ok(())
+}
+
+#[test]
+fn test_input_log() -> FaultResult {
+    init_logger();
+
+    // Adds pairs of numbers together until it reads a zero
+    let sample_prog = "3,20,1005,20,6,99,3,21,1,20,21,22,4,22,1105,1,0";
+
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.add_input(vec![3, 4]);
+    ic.run()?;
+    assert!(ic.is_waiting_on_input());
+    assert_eq!(ic.input_log(), &[3, 4]);
+
+    ic.add_input(vec![10, 20, 0, 99]);
+    ic.run()?;
+    assert!(ic.is_halted());
+
+    // The trailing 99 was never consumed so shouldn't be logged
+    assert_eq!(ic.input_log(), &[3, 4, 10, 20, 0]);
+    let output = ic.output();
+    assert_eq!(output, vec![7, 30]);
+
+    // Replaying the log against a fresh machine reproduces the session
+    let log = ic.input_log().to_vec();
+    ic.reset();
+    assert!(ic.input_log().is_empty());
+
+    ic.add_input(log);
+    ic.run()?;
+    assert!(ic.is_halted());
+    assert_eq!(ic.output(), output);
+
+    Ok(())
+}
+
+#[test]
+fn test_queueing_lines() {
+    init_logger();
+
+    let mut ic = IntCodeComputer::default();
+    ic.queue_line("north");
+    ic.queue_line("take");
+
+    // Input is stored in reverse order internally so it can be popped off the end
+    let queued: Vec<isize> = ic.input.iter().rev().cloned().collect();
+    assert_eq!(
+        queued,
+        vec![110, 111, 114, 116, 104, 10, 116, 97, 107, 101, 10]
+    );
+}
+
+#[test]
+fn test_output_cap() -> FaultResult {
+    init_logger();
+
+    // Outputs the value 1 forever
+    let sample_prog = "104,1,1105,1,0";
+
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.set_output_cap(Some(3));
+    assert_eq!(ic.run(), Err(Fault::OutputOverflow));
+    assert_eq!(ic.output(), vec![1, 1, 1, 1]);
+
+    // Programs that stay within the cap run normally
+    let mut ic = IntCodeComputer::from_str("104,1,104,2,99")?;
+    ic.set_output_cap(Some(2));
+    ic.run()?;
+    assert_eq!(ic.output(), vec![1, 2]);
+
+    Ok(())
+}
+
 #[test]
 fn test_parameter_mode_samples() -> FaultResult {
     init_logger();
@@ -546,12 +1346,206 @@ fn test_jump_instruction_samples5() -> FaultResult {
     Ok(())
 }
 
+#[test]
+fn test_breakpoints() -> FaultResult {
+    init_logger();
+
+    let prog = "1,9,10,3,2,3,11,0,99,30,40,50";
+    let mut ic = IntCodeComputer::from_str(prog)?;
+    ic.add_breakpoint(4);
+
+    // Stops before the multiplication, with only the addition having been performed
+    assert!(ic.run_to_breakpoint()?);
+    assert_eq!(ic.program_counter(), 4);
+    assert_eq!(ic.memory_str(), "1,9,10,70,2,3,11,0,99,30,40,50");
+
+    // Resuming steps off of the breakpoint and continues through to the halt
+    assert!(!ic.run_to_breakpoint()?);
+    assert!(ic.is_halted());
+    assert_eq!(ic.mem_read(0)?, 3500);
+
+    // Breakpoints survive a reset but can be cleared, and run() doesn't care about them
+    ic.reset();
+    ic.run()?;
+    assert_eq!(ic.mem_read(0)?, 3500);
+
+    ic.reset();
+    ic.clear_breakpoint(4);
+    assert!(!ic.run_to_breakpoint()?);
+    assert_eq!(ic.mem_read(0)?, 3500);
+
+    Ok(())
+}
+
+#[test]
+fn test_clone_paused_machine() -> FaultResult {
+    init_logger();
+
+    // Outputs a value before waiting on input, then outputs double whatever it was given
+    let mut original = IntCodeComputer::from_str("104,5,3,11,1002,11,2,11,4,11,99,0")?;
+    original.run()?;
+    assert!(original.is_waiting_on_input());
+
+    let mut branch = original.clone();
+    assert_eq!(branch.program_counter(), original.program_counter());
+    assert!(branch.is_waiting_on_input());
+    assert!(branch.memory == original.memory);
+    assert!(branch.original_memory == original.original_memory);
+
+    // Even the output that hasn't been collected yet comes along with the clone
+    assert_eq!(branch.output(), vec![5]);
+
+    branch.add_input(vec![21]);
+    branch.run()?;
+    assert!(branch.is_halted());
+    assert_eq!(branch.output(), vec![42]);
+
+    // None of that had any effect on the original which can take a different path
+    assert!(original.is_waiting_on_input());
+    assert_eq!(original.mem_read(11)?, 0);
+
+    original.add_input(vec![4]);
+    original.run()?;
+    assert_eq!(original.output(), vec![5, 8]);
+
+    Ok(())
+}
+
+#[test]
+fn test_output_as_string() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::from_str("104,72,104,105,104,10,99")?;
+    ic.run()?;
+    assert_eq!(ic.output_as_string(), "Hi\n");
+
+    // The output was drained in the process
+    assert_eq!(ic.output(), vec![]);
+
+    let mut ic = IntCodeComputer::from_str("104,62,104,19349,104,-1,99")?;
+    ic.run()?;
+    assert_eq!(ic.output_as_string(), "><19349><-1>");
+
+    Ok(())
+}
+
+#[test]
+fn test_pending_buffers() -> FaultResult {
+    init_logger();
+
+    // Echos two values back before halting
+    let mut ic = IntCodeComputer::from_str("3,0,4,0,3,0,4,0,99")?;
+    ic.add_input(vec![11, 22, 33]);
+    assert_eq!(ic.pending_input_len(), 3);
+    assert_eq!(ic.pending_output_len(), 0);
+
+    ic.step()?;
+    ic.step()?;
+    assert_eq!(ic.pending_input_len(), 2);
+    assert_eq!(ic.pending_output_len(), 1);
+    assert_eq!(ic.peek_output(), &[11]);
+
+    // Peeking doesn't drain anything
+    ic.run()?;
+    assert_eq!(ic.pending_input_len(), 1);
+    assert_eq!(ic.peek_output(), &[11, 22]);
+    assert_eq!(ic.pending_output_len(), 2);
+
+    assert_eq!(ic.output(), vec![11, 22]);
+    assert_eq!(ic.pending_output_len(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_run_until_output() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::from_str("104,7,104,8,99")?;
+    assert_eq!(ic.run_until_output()?, Some(7));
+    assert_eq!(ic.program_counter(), 2);
+    assert_eq!(ic.run_until_output()?, Some(8));
+    assert_eq!(ic.run_until_output()?, None);
+    assert!(ic.is_halted());
+
+    // Output that was already buffered isn't touched
+    let mut ic = IntCodeComputer::from_str("104,1,104,2,3,0,99")?;
+    ic.step()?;
+    assert_eq!(ic.run_until_output()?, Some(2));
+    assert_eq!(ic.run_until_output()?, None);
+    assert!(ic.is_waiting_on_input());
+    assert_eq!(ic.output(), vec![1]);
+
+    Ok(())
+}
+
+#[test]
+fn test_run_with_io() -> FaultResult {
+    init_logger();
+
+    let mut port = VecPort {
+        input: vec![42],
+        output: Vec::new(),
+    };
+
+    let mut ic = IntCodeComputer::from_str("3,0,4,0,99")?;
+    ic.run_with_io(&mut port)?;
+
+    assert!(ic.is_halted());
+    assert_eq!(port.output, vec![42]);
+    assert_eq!(ic.output(), vec![]);
+
+    // An empty port leaves the machine waiting until it has something to give
+    let mut port = VecPort {
+        input: Vec::new(),
+        output: Vec::new(),
+    };
+
+    let mut ic = IntCodeComputer::from_str("3,0,4,0,99")?;
+    ic.run_with_io(&mut port)?;
+    assert!(ic.is_waiting_on_input());
+    assert_eq!(port.output, vec![]);
+
+    port.input.push(-7);
+    ic.run_with_io(&mut port)?;
+    assert!(ic.is_halted());
+    assert_eq!(port.output, vec![-7]);
+
+    Ok(())
+}
+
+#[test]
+fn test_run_with_limit() -> FaultResult {
+    init_logger();
+
+    // Jumps straight back to itself forever
+    let mut ic = IntCodeComputer::from_str("1105,1,0")?;
+    assert_eq!(
+        ic.run_with_limit(1_000),
+        Err(Fault::StepLimitExceeded(1_000))
+    );
+    assert_eq!(ic.program_counter(), 0);
+
+    // Halting exactly on the last step allowed is still a success
+    let mut ic = IntCodeComputer::from_str("1,0,0,0,2,0,0,0,99")?;
+    ic.run_with_limit(2)?;
+    assert!(ic.is_halted());
+    assert_eq!(ic.mem_read(0)?, 4);
+
+    // Stopping to wait on input is within the budget as well
+    let mut ic = IntCodeComputer::from_str("3,0,99")?;
+    ic.run_with_limit(5)?;
+    assert!(ic.is_waiting_on_input());
+
+    Ok(())
+}
+
 #[test]
 fn test_system_reset() -> FaultResult {
     init_logger();
 
     let prog = "1,8,4,1,2,2,1,4,99";
-    let mut ic = IntCodeComputer::from_str(&prog)?;
+    let mut ic = IntCodeComputer::from_str(prog)?;
 
     ic.run()?;
     assert_eq!(ic.memory_str(), "1,101,4,1,404,2,1,4,99");
@@ -563,3 +1557,257 @@ fn test_system_reset() -> FaultResult {
 
     Ok(())
 }
+
+#[test]
+fn test_relative_base_quine() -> FaultResult {
+    init_logger();
+
+    // This program takes no input and produces a copy of itself as output, exercising relative
+    // reads and reads of memory beyond the end of the program
+    let sample_prog = "109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99";
+    let expected: Vec<isize> = sample_prog.split(',').map(|v| v.parse().unwrap()).collect();
+
+    assert_eq!(execute(sample_prog, vec![])?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_large_number_samples() -> FaultResult {
+    init_logger();
+
+    assert_eq!(
+        execute("1102,34915192,34915192,7,4,7,99,0", vec![])?,
+        vec![1_219_070_632_396_864]
+    );
+    assert_eq!(
+        execute("104,1125899906842624,99", vec![])?,
+        vec![1_125_899_906_842_624]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_relative_writes() -> FaultResult {
+    init_logger();
+
+    // Moves the relative base to 20, then reads input into and outputs from 5 past it
+    let sample_prog = "109,20,203,5,204,5,99";
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.add_input(vec![42]);
+    ic.run()?;
+
+    assert_eq!(ic.relative_base(), 20);
+    assert_eq!(ic.mem_read(25)?, 42);
+    assert_eq!(ic.output(), vec![42]);
+
+    // Relative destinations work for the three parameter operations as well
+    let sample_prog = "109,-3,21101,6,7,13,99";
+    let mut ic = IntCodeComputer::from_str(sample_prog)?;
+    ic.run()?;
+    assert_eq!(ic.mem_read(10)?, 13);
+
+    ic.reset();
+    assert_eq!(ic.relative_base(), 0);
+
+    // Immediate mode destinations still don't make any sense
+    let mut ic = IntCodeComputer::from_str("11101,6,7,5,99")?;
+    assert_eq!(ic.run(), Err(Fault::ParameterModeInvalid(0)));
+
+    Ok(())
+}
+
+#[test]
+fn test_step_back_relative_base() -> FaultResult {
+    init_logger();
+
+    let mut ic = IntCodeComputer::from_str("109,7,109,-2,99")?;
+    ic.enable_journaling(true);
+    ic.run()?;
+    assert_eq!(ic.relative_base(), 5);
+
+    ic.step_back()?;
+    assert_eq!(ic.relative_base(), 7);
+
+    ic.step_back()?;
+    assert_eq!(ic.relative_base(), 0);
+    assert_eq!(ic.program_counter(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_snapshot_restore() -> FaultResult {
+    init_logger();
+
+    // Outputs a value, reads one in, then outputs double whatever it was given
+    let mut icc = IntCodeComputer::from_str("104,5,3,11,1002,11,2,11,4,11,99,0")?;
+    icc.add_input(vec![21]);
+
+    icc.step()?;
+    icc.step()?;
+    let snap = icc.snapshot();
+    assert_eq!(icc.program_counter(), 4);
+
+    icc.run()?;
+    assert!(icc.is_halted());
+    assert_eq!(icc.output(), vec![5, 42]);
+
+    // Unlike a reset we end up mid-run, with the input consumed and the first output pending
+    icc.restore(&snap);
+    assert!(!icc.is_halted());
+    assert_eq!(icc.program_counter(), 4);
+    assert_eq!(icc.mem_read(11)?, 21);
+    assert_eq!(icc.input_log(), &[21]);
+    assert_eq!(icc.peek_output(), &[5]);
+    assert_eq!(icc.snapshot(), snap);
+
+    icc.run()?;
+    assert!(icc.is_halted());
+    assert_eq!(icc.output(), vec![5, 42]);
+
+    // The snapshot isn't used up by restoring it
+    icc.restore(&snap);
+    assert_eq!(icc.program_counter(), 4);
+
+    Ok(())
+}
+
+#[test]
+fn test_op_counting() -> FaultResult {
+    init_logger();
+
+    let mut icc = IntCodeComputer::from_str("1,9,10,3,2,3,11,0,99,30,40,50")?;
+    assert_eq!(icc.instructions_executed(), 0);
+    assert!(icc.op_histogram().is_empty());
+
+    icc.run()?;
+    assert_eq!(icc.instructions_executed(), 3);
+
+    let histogram = icc.op_histogram();
+    assert_eq!(histogram.get("ADD"), Some(&1));
+    assert_eq!(histogram.get("MUL"), Some(&1));
+    assert_eq!(histogram.get("HALT"), Some(&1));
+    assert_eq!(histogram.len(), 3);
+
+    // Poking a halted machine doesn't execute anything more
+    icc.step()?;
+    assert_eq!(icc.instructions_executed(), 3);
+
+    icc.reset();
+    assert_eq!(icc.instructions_executed(), 0);
+    assert!(icc.op_histogram().is_empty());
+
+    // A machine that has never been stepped hasn't executed anything, even sitting on a halt
+    let mut icc = IntCodeComputer::from_str("99")?;
+    assert_eq!(icc.instructions_executed(), 0);
+    assert!(icc.op_histogram().is_empty());
+
+    icc.step()?;
+    icc.step()?;
+    assert_eq!(icc.instructions_executed(), 1);
+    assert_eq!(icc.op_histogram().get("HALT"), Some(&1));
+
+    // Going back to a halt doesn't count it until the machine is stepped again
+    let mut icc = IntCodeComputer::from_str("1101,1,2,5,99,0")?;
+    icc.run()?;
+    let snap = icc.snapshot();
+    icc.reset();
+    icc.restore(&snap);
+    assert!(icc.is_halted());
+    assert_eq!(icc.instructions_executed(), 0);
+    assert!(icc.op_histogram().is_empty());
+
+    // Halting with a code is tallied under its own mnemonic
+    let mut icc = IntCodeComputer::from_str("1101,1,2,7,97,3,99,0")?;
+    icc.set_allow_extensions(true);
+    icc.run()?;
+    assert_eq!(icc.op_histogram().get("EXIT"), Some(&1));
+    assert_eq!(icc.op_histogram().get("HALT"), None);
+
+    // Waiting on input isn't counted until the input actually arrives
+    let mut icc = IntCodeComputer::from_str("3,5,4,5,99,0")?;
+    icc.enable_journaling(true);
+    icc.run()?;
+    icc.step()?;
+    assert!(icc.is_waiting_on_input());
+    assert_eq!(icc.instructions_executed(), 0);
+
+    icc.add_input(vec![7]);
+    icc.run()?;
+    assert_eq!(icc.instructions_executed(), 3);
+    assert_eq!(icc.op_histogram().get("IN"), Some(&1));
+
+    Ok(())
+}
+
+#[test]
+fn test_input_source() -> FaultResult {
+    init_logger();
+
+    // Echoes input back out forever
+    let echo = "3,7,4,7,1105,1,0,0";
+
+    let mut icc = IntCodeComputer::from_str(echo)?;
+    let mut counter = 0;
+    icc.set_input_source(Box::new(move || {
+        counter += 1;
+        if counter <= 5 {
+            Some(counter)
+        } else {
+            None
+        }
+    }));
+
+    // Anything queued up goes first
+    icc.add_input(vec![100]);
+
+    icc.run()?;
+    assert!(icc.is_waiting_on_input());
+    assert_eq!(icc.output(), vec![100, 1, 2, 3, 4, 5]);
+    assert_eq!(icc.input_log(), &[100, 1, 2, 3, 4, 5]);
+
+    icc.add_input(vec![6]);
+    icc.run()?;
+    assert_eq!(icc.output(), vec![6]);
+
+    // Clones don't share the source, and a reset removes it
+    let mut icc = IntCodeComputer::from_str(echo)?;
+    icc.set_input_source(Box::new(|| Some(1)));
+
+    let mut clone = icc.clone();
+    clone.run()?;
+    assert!(clone.is_waiting_on_input());
+    assert_eq!(clone.output(), vec![]);
+
+    for _ in 0..6 {
+        icc.step()?;
+    }
+    assert_eq!(icc.output(), vec![1, 1]);
+
+    icc.reset();
+    icc.run()?;
+    assert!(icc.is_waiting_on_input());
+
+    // Values pulled from the source are put back into the queue when stepping backwards
+    let mut icc = IntCodeComputer::from_str(echo)?;
+    icc.enable_journaling(true);
+    let mut next = 10;
+    icc.set_input_source(Box::new(move || {
+        next += 1;
+        Some(next)
+    }));
+
+    icc.step()?;
+    assert_eq!(icc.input_log(), &[11]);
+    icc.step_back()?;
+    assert_eq!(icc.input_log(), &[] as &[isize]);
+    assert_eq!(icc.pending_input_len(), 1);
+
+    icc.step()?;
+    icc.step()?;
+    assert_eq!(icc.output(), vec![11]);
+
+    Ok(())
+}
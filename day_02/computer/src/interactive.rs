@@ -0,0 +1,98 @@
+//! Some of the puzzles are text adventures that are far more fun to play by hand than to solve
+//! with code. This wires a machine up to a terminal, or anything else that looks like one.
+
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::{Fault, IntCodeComputer};
+
+fn io_fault(err: io::Error) -> Fault {
+    Fault::IoError(err.to_string())
+}
+
+/// Runs the program against stdin and stdout. See `run_interactive_with()` for the details.
+pub fn run_interactive(program: &str) -> Result<(), Fault> {
+    let stdin = io::stdin();
+    run_interactive_with(program, stdin.lock(), io::stdout())
+}
+
+/// Runs the program until it halts, writing its output as ASCII text and reading another line of
+/// input each time it blocks waiting for more. Output values that aren't ASCII, such as the final
+/// answer many of these programs end with, are written out as numbers on their own line instead.
+///
+/// If the input runs out while the program is still waiting for more this will fault with an
+/// `IoError`, as the program has no way of ever finishing.
+pub fn run_interactive_with(
+    program: &str,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> Result<(), Fault> {
+    let mut icc = IntCodeComputer::from_str(program)?;
+
+    loop {
+        icc.run()?;
+
+        for value in icc.output() {
+            match value {
+                0..=127 => output.write_all(&[value as u8]),
+                _ => writeln!(output, "{}", value),
+            }
+            .map_err(io_fault)?;
+        }
+        output.flush().map_err(io_fault)?;
+
+        if icc.is_halted() {
+            return Ok(());
+        }
+
+        let mut line = String::new();
+        if input.read_line(&mut line).map_err(io_fault)? == 0 {
+            return Err(Fault::IoError(
+                "input ended while the program was waiting for more".to_string(),
+            ));
+        }
+
+        // The newline is added back when the line is queued, this just makes sure a Windows style
+        // line ending doesn't leave a stray carriage return behind
+        icc.queue_line(line.trim_end_matches(&['\r', '\n'][..]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Echoes each character it reads until it has echoed a newline, then halts.
+    const ECHO_LINE_PROG: &str = "3,100,4,100,1008,100,10,101,1006,101,0,99";
+
+    #[test]
+    fn test_interactive_echo() -> Result<(), Fault> {
+        let mut output = Vec::new();
+        run_interactive_with(ECHO_LINE_PROG, Cursor::new("hello\r\n"), &mut output)?;
+
+        assert_eq!(String::from_utf8(output).unwrap(), "hello\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interactive_output() -> Result<(), Fault> {
+        // Prompts with "?\n", then outputs a value too large to be ASCII once it has a line
+        let prog = "104,63,104,10,3,100,104,1000,99";
+
+        let mut output = Vec::new();
+        run_interactive_with(prog, Cursor::new("x\n"), &mut output)?;
+
+        assert_eq!(String::from_utf8(output).unwrap(), "?\n1000\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interactive_input_exhausted() {
+        let result = run_interactive_with(ECHO_LINE_PROG, Cursor::new(""), Vec::new());
+        assert!(matches!(result, Err(Fault::IoError(_))));
+    }
+}
@@ -1,3 +1,10 @@
+pub mod assembler;
 pub mod int_code_computer;
+pub mod interactive;
 
-pub use int_code_computer::{Fault, IntCodeComputer};
+pub use assembler::assemble;
+pub use int_code_computer::{
+    execute, find_all_noun_verb, find_noun_verb, load_program, run_program_for_addr0, Fault,
+    IntCodeComputer, IoPort, MemoryStats, Snapshot, MEMORY_LIMIT, MEMORY_SIZE,
+};
+pub use interactive::{run_interactive, run_interactive_with};
@@ -1,25 +1,57 @@
-use std::fs::File;
-use std::io::Read;
+use std::collections::HashSet;
 use std::str::FromStr;
 
-use computer::IntCodeComputer;
+use common::AocError;
+use computer::{find_noun_verb, load_program, run_program_for_addr0, IntCodeComputer};
 
-fn main() {
-    let mut in_dat_fh = File::open("./data/input_02.txt").unwrap();
-    let mut in_dat = String::new();
+/// Reversing the execution of a day 2 program only works if the instructions that run are the
+/// ones that can be read straight out of the program. Without any jumps the only way that can
+/// break is by a write landing on an opcode that hasn't run yet, which would change what that
+/// instruction does. This walks the instructions in order and checks exactly that, without
+/// actually running anything. Programs that can't be parsed, or contain anything other than the
+/// day 2 instructions, can't be shown to be safe.
+pub fn is_statically_reversible(program: &str) -> bool {
+    let memory: Vec<isize> = match IntCodeComputer::from_str(program) {
+        Ok(icc) => icc.memory_map().into_iter().map(|(_, val)| val).collect(),
+        Err(_) => return false,
+    };
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
-    let mut icc = IntCodeComputer::from_str(&in_dat).unwrap();
+    let mut pc = 0;
+    let mut destinations = HashSet::new();
 
-    // The instructions indicate to make these replacments before running
-    icc.store(1, 12).unwrap();
-    icc.store(2, 2).unwrap();
+    while let Some(opcode) = memory.get(pc) {
+        // Only the writes made before reaching an opcode are able to change it
+        if destinations.contains(&(pc as isize)) {
+            return false;
+        }
 
-    if let Err(err) = icc.run() {
-        println!("Program crashed with error: {:?}", err);
-    };
+        match opcode {
+            1 | 2 => {
+                let dest = match memory.get(pc + 3) {
+                    Some(dest) => dest,
+                    None => return false,
+                };
+
+                destinations.insert(*dest);
+            }
+            99 => return true,
+            _ => return false,
+        }
+
+        pc += 4;
+    }
+
+    // Ran off the end of the program without ever halting
+    false
+}
+
+fn main() -> Result<(), AocError> {
+    let in_dat = load_program("./data/input_02.txt")?;
+
+    // The instructions indicate to make these replacments before running
+    let answer = run_program_for_addr0(&in_dat, &[(1, 12), (2, 2)])?;
+    println!("Answer to step 1 is: {}", answer);
 
-    println!("Answer to step 1 is: {}", icc.mem_read(0).unwrap());
     println!("Brute force searching the answer to step 2...");
 
     // Alright so there are two possibilities for how I could go about finding the answer to step
@@ -29,18 +61,36 @@ fn main() {
     // still possible because there are no jumps only linear advancement, the only failure
     // possibility here is if one of the opcodes got overwritten by the program... which is
     // possible... Nah I'm just going to bruteforce it.
-    for noun in 0..100 {
-        for verb in 0..100 {
-            icc.reset();
+    match find_noun_verb(&in_dat, 19_690_720)? {
+        Some((noun, verb)) => println!("Found a valid answer: {:0>2}{:0>2}", noun, verb),
+        None => println!("No noun and verb produce the expected output"),
+    }
+
+    println!(
+        "Reversing execution would have been safe: {}",
+        is_statically_reversible(&in_dat)
+    );
 
-            icc.store(1, noun).unwrap();
-            icc.store(2, verb).unwrap();
+    Ok(())
+}
 
-            icc.run().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            if icc.mem_read(0).unwrap() == 19_690_720 {
-                println!("Found a valid answer: {:0>2}{:0>2}", noun, verb);
-            }
-        }
+    #[test]
+    fn test_static_reversibility() {
+        // Writes either land on data or on an instruction that has already run
+        assert!(is_statically_reversible("1,9,10,3,2,3,11,0,99,30,40,50"));
+        assert!(is_statically_reversible("99"));
+
+        // The first addition changes the halt into a multiplication
+        assert!(!is_statically_reversible("1,0,0,4,99,5,6,0,99"));
+        assert!(!is_statically_reversible("1,1,1,8,2,0,0,0,99"));
+
+        // Anything that can't be reasoned about isn't considered safe
+        assert!(!is_statically_reversible("1,0,0,0"));
+        assert!(!is_statically_reversible("1,0,0,0,1005,0,0,99"));
+        assert!(!is_statically_reversible("1,x,0,0,99"));
     }
 }
@@ -1,8 +1,12 @@
 use std::cmp;
+use std::collections::HashSet;
+use std::fmt;
 use std::fs::File;
 use std::io::Read;
+use std::ops::{Add, Neg, Sub};
 use std::str::FromStr;
 
+use common::{AocError, Orientation, Point};
 use itertools::Itertools;
 
 #[derive(Debug, PartialEq)]
@@ -13,6 +17,17 @@ pub enum Direction {
     Up(usize),
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Down(v) => write!(f, "D{}", v),
+            Self::Left(v) => write!(f, "L{}", v),
+            Self::Right(v) => write!(f, "R{}", v),
+            Self::Up(v) => write!(f, "U{}", v),
+        }
+    }
+}
+
 impl FromStr for Direction {
     type Err = String;
 
@@ -55,25 +70,113 @@ pub struct Location {
 
 impl Location {
     pub fn apply_direction(&self, dir: &Direction) -> Self {
-        match dir {
-            Direction::Down(v) => Self::new(self.x, self.y - *v as isize, self.distance + *v),
-            Direction::Left(v) => Self::new(self.x - *v as isize, self.y, self.distance + *v),
-            Direction::Right(v) => Self::new(self.x + *v as isize, self.y, self.distance + *v),
-            Direction::Up(v) => Self::new(self.x, self.y + *v as isize, self.distance + *v),
-        }
+        let movement = match dir {
+            Direction::Down(v) => Self::new(0, -(*v as isize), *v),
+            Direction::Left(v) => Self::new(-(*v as isize), 0, *v),
+            Direction::Right(v) => Self::new(*v as isize, 0, *v),
+            Direction::Up(v) => Self::new(0, *v as isize, *v),
+        };
+
+        self.clone() + movement
+    }
+
+    /// The bare coordinates of this location as a tuple, without the distance travelled to reach
+    /// it.
+    pub fn coord_key(&self) -> (isize, isize) {
+        (self.x, self.y)
+    }
+
+    /// The largest of the differences along either axis between this location and another, which
+    /// is the number of moves it would take if diagonal moves were allowed.
+    pub fn chebyshev_distance(&self, other: &Self) -> usize {
+        Point::from(self).chebyshev_distance(&Point::from(other))
+    }
+
+    /// The straight line distance between this location and another, ignoring the grid entirely.
+    pub fn euclidean_distance(&self, other: &Self) -> f64 {
+        Point::from(self).euclidean_distance(&Point::from(other))
     }
 
     /// Calculates the absolute sum of differences between this location and another provided one.
     pub fn manhattan_distance(&self, other: &Self) -> usize {
-        let x_dist: usize = (self.x - other.x).abs() as usize;
-        let y_dist: usize = (self.y - other.y).abs() as usize;
-
-        x_dist + y_dist
+        Point::from(self).manhattan_distance(&Point::from(other))
     }
 
     pub fn new(x: isize, y: isize, distance: usize) -> Self {
         Self { x, y, distance }
     }
+
+    /// The coordinates of this location without the distance travelled to reach it, suitable for
+    /// use as a key when deduplicating locations by position.
+    pub fn position_key(&self) -> PositionKey {
+        let (x, y) = self.coord_key();
+        PositionKey(x, y)
+    }
+}
+
+/// Adding locations treats the right hand side as a movement, the coordinates are offset by it and
+/// the distance it took is added onto the distance already travelled.
+impl Add for Location {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.x + other.x,
+            self.y + other.y,
+            self.distance + other.distance,
+        )
+    }
+}
+
+/// The reverse of `Add`, this gives the movement needed to get from the right hand side to the
+/// left. The distance is how much further the left hand side travelled, which can't go below zero.
+impl Sub for Location {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(
+            self.x - other.x,
+            self.y - other.y,
+            self.distance.saturating_sub(other.distance),
+        )
+    }
+}
+
+/// Mirrors the location through the origin. Going the opposite direction is still the same
+/// amount of travel so the distance is kept as is.
+impl Neg for Location {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, self.distance)
+    }
+}
+
+/// Two locations at the same coordinates aren't equal if they were reached after travelling
+/// different distances. That is what we want most of the time, but the same crossing can be found
+/// through different pairs of segments so this only considers the coordinates allowing them to be
+/// deduplicated with a `HashSet`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PositionKey(pub isize, pub isize);
+
+/// A location is a point that also remembers how far a wire travelled to reach it. Points coming
+/// from elsewhere haven't been reached by a wire so start with no distance travelled.
+impl From<Point> for Location {
+    fn from(point: Point) -> Self {
+        Self::new(point.x, point.y, 0)
+    }
+}
+
+impl From<&Location> for Point {
+    fn from(loc: &Location) -> Self {
+        Point::new(loc.x, loc.y)
+    }
+}
+
+impl From<&Location> for PositionKey {
+    fn from(loc: &Location) -> Self {
+        loc.position_key()
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -180,10 +283,10 @@ impl LineSegment {
     /// this is true for any of the combinations then then the line segments overlap.
     pub fn intersects(&self, other: &Self) -> bool {
         let orientations: [Orientation; 4] = [
-            Orientation::from_three_locations(&self.0, &self.1, &other.0),
-            Orientation::from_three_locations(&self.0, &self.1, &other.1),
-            Orientation::from_three_locations(&other.0, &other.1, &self.0),
-            Orientation::from_three_locations(&other.0, &other.1, &self.1),
+            Orientation::from_three_points(&self.0, &self.1, &other.0),
+            Orientation::from_three_points(&self.0, &self.1, &other.1),
+            Orientation::from_three_points(&other.0, &other.1, &self.0),
+            Orientation::from_three_points(&other.0, &other.1, &self.1),
         ];
 
         // The first case is proven true through these orientation differences, it seems like this can
@@ -223,43 +326,147 @@ impl LineSegment {
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum Orientation {
-    Clockwise,
-    CounterClockwise,
-    Colinear,
-}
-
-impl Orientation {
-    /// This caculates the three point orientation of any three points so we can determine the
-    /// relation between the points for the edge and general cases of segment intersection. This is
-    /// calculated using the slope between p1/p2, and p2/p3. If the slope is the same
-    /// (difference of zero) the two lines are colinear. If the slope of p1/p2 is less than p2/p3
-    /// than the p2/p3 slope is bending counterclockwise from the p1/p2 slope, when it's more it's
-    /// bending more clockwise from the slope.
-    ///
-    /// These orientations can be used to quickly check whether the segments intersect at all. If
-    /// so we can then go on to attempt to solve the equations to get the answer.
-    pub fn from_three_locations(l1: &Location, l2: &Location, l3: &Location) -> Self {
-        let orientation = (l2.y - l1.y) * (l3.x - l2.x) - (l2.x - l1.x) * (l3.y - l2.y);
-
-        match orientation {
-            orient if orient < 0 => Self::CounterClockwise,
-            orient if orient > 0 => Self::Clockwise,
-            _ => Self::Colinear,
+/// Reduces a list of directions down to the net displacement of each run of moves along the same
+/// axis. Consecutive moves along the same axis are combined into a single move (`R5,L3` becomes
+/// `R2`) and any run that nets out to nothing is dropped entirely, which may in turn allow the
+/// moves on either side of it to be combined.
+///
+/// This is a net-displacement operation, not a path preserving one. The resulting wire ends up in
+/// the same place but takes a different shape to get there, so it will not intersect with other
+/// wires in the same places the original did.
+pub fn normalize_directions(dirs: &[Direction]) -> Vec<Direction> {
+    // Track each move as whether it's on the horizontal axis and its signed magnitude, which makes
+    // combining opposing moves simple addition.
+    let mut net_moves: Vec<(bool, isize)> = Vec::new();
+
+    for dir in dirs {
+        let (horizontal, amount) = match dir {
+            Direction::Down(v) => (false, -(*v as isize)),
+            Direction::Left(v) => (true, -(*v as isize)),
+            Direction::Right(v) => (true, *v as isize),
+            Direction::Up(v) => (false, *v as isize),
+        };
+
+        match net_moves.last_mut() {
+            Some((last_horizontal, last_amount)) if *last_horizontal == horizontal => {
+                *last_amount += amount;
+
+                if *last_amount == 0 {
+                    net_moves.pop();
+                }
+            }
+            _ => {
+                if amount != 0 {
+                    net_moves.push((horizontal, amount));
+                }
+            }
         }
     }
+
+    net_moves
+        .into_iter()
+        .map(|(horizontal, amount)| match (horizontal, amount < 0) {
+            (true, true) => Direction::Left(amount.unsigned_abs()),
+            (true, false) => Direction::Right(amount as usize),
+            (false, true) => Direction::Down(amount.unsigned_abs()),
+            (false, false) => Direction::Up(amount as usize),
+        })
+        .collect()
+}
+
+/// The different ways two wire segments can meet. Wires that cross each other are what the puzzle
+/// is interested in, while wires that run along the same track for a while share a whole span of
+/// locations rather than crossing at any one of them.
+#[derive(Debug, PartialEq)]
+pub enum CrossingKind {
+    Transverse(Location),
+    Overlap(Vec<Location>),
+    None,
+}
+
+/// Classifies how the two segments meet, if they do at all. Colinear segments that share any
+/// locations are always an overlap, even when it is only a single shared endpoint.
+pub fn crossing_kind(a: &LineSegment, b: &LineSegment) -> CrossingKind {
+    if !a.intersects(b) {
+        return CrossingKind::None;
+    }
+
+    let shared = overlap_points(a, b);
+    if !shared.is_empty() {
+        return CrossingKind::Overlap(shared);
+    }
+
+    match a.intersecting_location(b) {
+        Some(loc) => CrossingKind::Transverse(loc),
+        None => CrossingKind::None,
+    }
+}
+
+/// When two segments lie on the same line and overlap, `intersecting_location()` gives up as there
+/// is no single intersection. For our purposes only whole number locations matter, so this
+/// instead returns every one of those the two segments share, in order walking along the first
+/// segment. Segments that aren't colinear and overlapping produce an empty list.
+///
+/// The distance of each location is the combined distance both wires travelled to reach it, the
+/// same as the intersections `intersecting_location()` produces.
+pub fn overlap_points(a: &LineSegment, b: &LineSegment) -> Vec<Location> {
+    let colinear = Orientation::from_three_points(&a.0, &a.1, &b.0) == Orientation::Colinear
+        && Orientation::from_three_points(&a.0, &a.1, &b.1) == Orientation::Colinear;
+
+    if !colinear {
+        return Vec::new();
+    }
+
+    let dx = a.1.x - a.0.x;
+    let dy = a.1.y - a.0.y;
+
+    // Walking in steps of the reduced slope visits every whole number location on the segment
+    let steps = gcd(dx.unsigned_abs(), dy.unsigned_abs()) as isize;
+    let (step_x, step_y) = if steps == 0 {
+        (0, 0)
+    } else {
+        (dx / steps, dy / steps)
+    };
+
+    (0..=steps)
+        .map(|i| Location::new(a.0.x + i * step_x, a.0.y + i * step_y, 0))
+        .filter(|point| b.is_present(point))
+        .map(|point| {
+            let distance = a.0.distance
+                + a.0.manhattan_distance(&point)
+                + b.0.distance
+                + b.0.manhattan_distance(&point);
+
+            Location::new(point.x, point.y, distance)
+        })
+        .collect()
 }
 
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The inverse of `parse_directions()`, rendering a path back into the comma separated format the
+/// puzzle input uses. Handy for generating test fixtures.
+pub fn directions_to_string(dirs: &[Direction]) -> String {
+    dirs.iter().map(|d| d.to_string()).join(",")
+}
+
+/// Parses a comma separated wire path such as `R8,U5`. Errors point out which token (counting
+/// from zero) couldn't be parsed along with the token itself.
 pub fn parse_directions(input: &str) -> Result<Vec<Direction>, String> {
     let directions = input.trim().split(',');
 
     let mut res: Vec<Direction> = Vec::new();
-    for dir in directions {
-        match Direction::from_str(&dir) {
+    for (idx, dir) in directions.enumerate() {
+        match Direction::from_str(dir) {
             Ok(d) => res.push(d),
             Err(err) => {
-                return Err(err);
+                return Err(format!("token {} ('{}') invalid: {}", idx, dir, err));
             }
         }
     }
@@ -267,12 +474,60 @@ pub fn parse_directions(input: &str) -> Result<Vec<Direction>, String> {
     Ok(res)
 }
 
+/// Collects every coordinate each of the wires passes through, not just the corners, and returns
+/// the ones they have in common. This is the grid based alternative to checking every pair of
+/// segments against each other. The memory it needs grows with the length of the wires, but each
+/// point is only ever looked at once.
+pub fn overlapping_positions(first: &[Location], second: &[Location]) -> HashSet<PositionKey> {
+    let trace = |wire: &[Location]| -> HashSet<PositionKey> {
+        let mut positions = HashSet::new();
+
+        for (start, end) in wire.iter().tuple_windows() {
+            let step = Location::new((end.x - start.x).signum(), (end.y - start.y).signum(), 1);
+            let mut current = start.clone();
+
+            positions.insert(current.position_key());
+            while current.position_key() != end.position_key() {
+                current = current + step.clone();
+                positions.insert(current.position_key());
+            }
+        }
+
+        positions
+    };
+
+    let first_positions = trace(first);
+    trace(second)
+        .into_iter()
+        .filter(|pos| first_positions.contains(pos))
+        .collect()
+}
+
+/// Walks along the wire described by the provided segments and returns where it is once the
+/// requested distance has been traveled (its signal delay). If the wire isn't long enough to reach
+/// that many steps this will return None.
+pub fn position_at_step(segments: &[LineSegment], step: usize) -> Option<Location> {
+    let segment = segments
+        .iter()
+        .find(|seg| seg.0.distance <= step && step <= seg.1.distance)?;
+
+    let remaining = (step - segment.0.distance) as isize;
+    let x_dir = (segment.1.x - segment.0.x).signum();
+    let y_dir = (segment.1.y - segment.0.y).signum();
+
+    Some(Location::new(
+        segment.0.x + x_dir * remaining,
+        segment.0.y + y_dir * remaining,
+        step,
+    ))
+}
+
 pub fn relative_to_absolute(start: Location, directions: &[Direction]) -> Vec<Location> {
     let mut points: Vec<Location> = Vec::new();
     let mut current = start;
 
     for dir in directions.iter() {
-        let new_current = current.apply_direction(&dir);
+        let new_current = current.apply_direction(dir);
         points.push(current);
         current = new_current;
     }
@@ -301,16 +556,229 @@ pub fn location_set_to_line_set(location_set: Vec<Location>) -> Vec<LineSegment>
     line_segments
 }
 
-fn main() {
-    let mut in_dat_fh = File::open("./data/input_03.txt").unwrap();
+/// Searches every pair of segments from the two wires for the locations where they cross. The
+/// distance of each returned location is the combined number of steps both wires took to reach
+/// it. As both wires start at the same place the origin will always be included.
+pub fn find_intersections(first: &[LineSegment], second: &[LineSegment]) -> Vec<Location> {
+    let mut intersection_list: Vec<Location> = Vec::new();
+
+    for first_line in first {
+        for second_line in second {
+            if first_line.intersects(second_line) {
+                // We know these two lines intersect now, I just have to calculate the position
+                // they intersect at.
+                match first_line.intersecting_location(second_line) {
+                    Some(loc) => intersection_list.push(loc),
+                    None => {
+                        // This is a weird edge case where the two line segments representing the
+                        // same line and are overlapping. This means one end of the line segment is
+                        // in the other one. We need to figure out which one then add that to our
+                        // list
+                        if first_line.is_present(&second_line.0) {
+                            intersection_list.push(second_line.0.clone());
+                        } else if first_line.is_present(&second_line.1) {
+                            intersection_list.push(second_line.1.clone());
+                        } else {
+                            // This should never be the case but log it in case something extremely
+                            // weird happens...
+                            println!(
+                                "Weird intersection case: {:?}, {:?}",
+                                first_line, second_line
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    intersection_list
+}
+
+/// Calculates the smallest box containing every location of all of the provided wires, returned
+/// as the minimum and maximum corners. This is used to size the grid needed to render the wires.
+/// When there aren't any locations this will be a zero sized box at the origin.
+pub fn wires_bounding_box(wires: &[&[Location]]) -> (Location, Location) {
+    let mut locations = wires.iter().flat_map(|w| w.iter());
+
+    let first = match locations.next() {
+        Some(loc) => loc,
+        None => return (Location::new(0, 0, 0), Location::new(0, 0, 0)),
+    };
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+
+    for loc in locations {
+        min_x = cmp::min(min_x, loc.x);
+        min_y = cmp::min(min_y, loc.y);
+        max_x = cmp::max(max_x, loc.x);
+        max_y = cmp::max(max_y, loc.y);
+    }
+
+    (
+        Location::new(min_x, min_y, 0),
+        Location::new(max_x, max_y, 0),
+    )
+}
+
+/// The largest width or height of the wire bounding box `render_grid()` is willing to draw. The
+/// real puzzle input spans hundreds of thousands of cells in each direction which isn't something
+/// anyone wants printed to their terminal.
+pub const MAX_RENDER_SIZE: usize = 200;
+
+/// Plots the two wires onto a character grid the same way the puzzle examples are drawn. The
+/// origin is marked with `o`, the wires are drawn with `-` and `|` with a `+` wherever they turn,
+/// and `X` marks every spot the two wires cross. A one cell border of `.` surrounds the whole
+/// thing.
+///
+/// Wires without any locations simply aren't drawn, so with nothing to draw the grid only holds
+/// the origin. This is only meant for looking at small examples, if the wires cover more than
+/// `MAX_RENDER_SIZE` cells in either direction a message saying so is returned instead of a grid.
+pub fn render_grid(first: &[Location], second: &[Location]) -> String {
+    let origin = [Location::new(0, 0, 0)];
+    let (min, max) = wires_bounding_box(&[&origin, first, second]);
+
+    let width = (max.x - min.x) as usize + 1;
+    let height = (max.y - min.y) as usize + 1;
+
+    if width > MAX_RENDER_SIZE || height > MAX_RENDER_SIZE {
+        return format!(
+            "wires cover {}x{} cells which is larger than the {}x{} limit",
+            width, height, MAX_RENDER_SIZE, MAX_RENDER_SIZE
+        );
+    }
+
+    // Each wire gets drawn on its own layer first so crossings can be found where both layers have
+    // something drawn in the same cell
+    let draw_wire = |wire: &[Location]| -> Vec<Vec<char>> {
+        let mut layer = vec![vec!['.'; width]; height];
+        let mut plot = |x: isize, y: isize, chr: char| {
+            layer[(y - min.y) as usize][(x - min.x) as usize] = chr;
+        };
+
+        for (start, end) in wire.iter().tuple_windows() {
+            let x_dir = (end.x - start.x).signum();
+            let y_dir = (end.y - start.y).signum();
+            let chr = if y_dir == 0 { '-' } else { '|' };
+
+            let (mut x, mut y) = (start.x, start.y);
+            loop {
+                plot(x, y, chr);
+
+                if (x, y) == (end.x, end.y) {
+                    break;
+                }
+
+                x += x_dir;
+                y += y_dir;
+            }
+        }
+
+        // Every point in the middle of the wire is somewhere it changed direction
+        if wire.len() > 2 {
+            for corner in &wire[1..wire.len() - 1] {
+                plot(corner.x, corner.y, '+');
+            }
+        }
+
+        layer
+    };
+
+    let first_layer = draw_wire(first);
+    let second_layer = draw_wire(second);
+
+    let border = ".".repeat(width + 2);
+    let mut output = vec![border.clone()];
+
+    // Up is the positive direction for the wires, so the rows have to be drawn top down
+    for row in (0..height).rev() {
+        let mut line = String::from(".");
+
+        for col in 0..width {
+            let chr = match (first_layer[row][col], second_layer[row][col]) {
+                _ if (col as isize + min.x, row as isize + min.y) == (0, 0) => 'o',
+                ('.', other) => other,
+                (other, '.') => other,
+                _ => 'X',
+            };
+
+            line.push(chr);
+        }
+
+        line.push('.');
+        output.push(line);
+    }
+
+    output.push(border);
+
+    output.join("\n")
+}
+
+/// The answers to both parts of the puzzle along with how many intersections they were chosen
+/// from. The origin is never considered an intersection for any of these values.
+#[derive(Debug, PartialEq)]
+pub struct WireAnalysis {
+    pub nearest_manhattan: Option<usize>,
+    pub fewest_steps: Option<usize>,
+    pub intersection_count: usize,
+}
+
+/// Both wires start at the origin, so they always cross there. That never counts as an
+/// intersection for any of the puzzle answers.
+const WIRE_START: PositionKey = PositionKey(0, 0);
+
+/// Filters out any of the intersections sitting on the provided origin, leaving only the ones that
+/// count towards the puzzle answers.
+fn crossings(
+    intersections: &[Location],
+    origin: PositionKey,
+) -> impl Iterator<Item = &Location> + '_ {
+    intersections
+        .iter()
+        .filter(move |i| i.position_key() != origin)
+}
+
+/// The smallest combined signal delay of any of the provided intersections (part two). The origin
+/// is where both wires start, so the zero delay it would have is ignored.
+pub fn closest_by_steps(intersections: &[Location]) -> Option<usize> {
+    crossings(intersections, WIRE_START)
+        .map(|i| i.distance)
+        .min()
+}
+
+/// The smallest manhattan distance between the origin and any of the provided intersections
+/// (part one). Intersections sitting on the origin itself are ignored.
+pub fn closest_by_manhattan(intersections: &[Location], origin: &Location) -> Option<usize> {
+    crossings(intersections, origin.position_key())
+        .map(|i| origin.manhattan_distance(i))
+        .min()
+}
+
+/// Finds the intersections of the two wires and calculates both the nearest intersection to the
+/// origin and the intersection with the shortest combined signal delay.
+pub fn analyze_wires(first: &[LineSegment], second: &[LineSegment]) -> WireAnalysis {
+    let intersections = find_intersections(first, second);
+
+    WireAnalysis {
+        nearest_manhattan: closest_by_manhattan(&intersections, &Location::new(0, 0, 0)),
+        fewest_steps: closest_by_steps(&intersections),
+        intersection_count: crossings(&intersections, WIRE_START).count(),
+    }
+}
+
+fn main() -> Result<(), AocError> {
+    let mut in_dat_fh = File::open("./data/input_03.txt")?;
     let mut in_dat = String::new();
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
-    let lines: Vec<&str> = in_dat.lines().collect();
+    in_dat_fh.read_to_string(&mut in_dat)?;
+    let wires: Vec<Vec<Direction>> = in_dat
+        .lines()
+        .map(parse_directions)
+        .collect::<Result<_, _>>()?;
 
-    let location_set: Option<(Vec<Location>, Vec<Location>)> = lines
+    let location_set: Option<(Vec<Location>, Vec<Location>)> = wires
         .iter()
-        .map(|l| relative_to_absolute(Location::new(0, 0, 0), &parse_directions(&l).unwrap()))
+        .map(|w| relative_to_absolute(Location::new(0, 0, 0), w))
         .collect_tuple();
 
     // TODO:
@@ -344,88 +812,29 @@ fn main() {
     // 3. Return the distance (w + h) of the intersection with the lowest manhatten distance. Also
     //    straight forward, this just needs to do a min() over the results from the last step.
 
-    let (first_location_set, second_location_set) = if let Some(ls) = location_set {
-        ls
-    } else {
-        println!("Input file didn't have exactly two input lines.");
-        std::process::exit(1);
+    let (first_location_set, second_location_set) = match location_set {
+        Some(ls) => ls,
+        None => return Err("input file didn't have exactly two input lines".into()),
     };
 
-    let mut intersection_list: Vec<Location> = Vec::new();
-
     let first_line_set = location_set_to_line_set(first_location_set);
     let second_line_set = location_set_to_line_set(second_location_set);
 
-    for first_line in &first_line_set {
-        for second_line in &second_line_set {
-            if first_line.intersects(&second_line) {
-                // We know these two lines intersect now, I just have to calculate the position
-                // they intersect at.
-                match first_line.intersecting_location(&second_line) {
-                    Some(loc) => intersection_list.push(loc),
-                    None => {
-                        // This is a weird edge case where the two line segments representing the
-                        // same line and are overlapping. This means one end of the line segment is
-                        // in the other one. We need to figure out which one then add that to our
-                        // list
-                        if first_line.is_present(&second_line.0) {
-                            intersection_list.push(second_line.0.clone());
-                        } else if first_line.is_present(&second_line.1) {
-                            intersection_list.push(second_line.1.clone());
-                        } else {
-                            // This should never be the case but log it in case something extremely
-                            // weird happens...
-                            println!(
-                                "Weird intersection case: {:?}, {:?}",
-                                first_line, second_line
-                            );
-                        }
-                    }
-                }
-            }
-        }
-    }
-
+    let analysis = analyze_wires(&first_line_set, &second_line_set);
     println!(
         "Found {} intersections in data set",
-        intersection_list.len()
+        analysis.intersection_count
     );
 
-    // Only thing left is to calculate the distances and return the smallest intersection. We'll be
-    // calculating from the origin, and due to how the relative to absolute positioning works, our
-    // first intersection should be at the origin (which we also want to remove so we can get a
-    // valid answer).
-    let mut intersection_iter = intersection_list.iter();
-    let origin = if let Some(o) = intersection_iter.next() {
-        if o != &Location::new(0, 0, 0) {
-            println!(
-                "Expectation fail, the first intersection wasn't the origin: {:?}",
-                o
-            );
-            std::process::exit(1);
-        }
-
-        o
-    } else {
-        println!("Expectation fail, there should be at least one intersection right?");
-        std::process::exit(1);
-    };
-
-    match intersection_iter
-        .map(|il| origin.manhattan_distance(&il))
-        .min()
-    {
+    match analysis.nearest_manhattan {
         Some(min_dist) => println!("Minimum distance to intersection is: {}", min_dist),
         None => println!("Couldn't find the minimum distance..."),
     }
 
-    let mut intersection_iter = intersection_list.iter();
-    // Discard the first one as it is our origin and has a distance of 0
-    intersection_iter.next();
-
     // For part two we need to find the intersection that had the smallest total distance
-    let min_location = intersection_iter.map(|l| l.distance).min();
-    println!("Minimum intersection distance: {:?}", min_location);
+    println!("Minimum intersection distance: {:?}", analysis.fewest_steps);
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use super::*;
 
 #[test]
@@ -16,6 +18,154 @@ fn test_manhattan_distance() {
     }
 }
 
+#[test]
+fn test_chebyshev_distance() {
+    let reference_point = Location::new(0, 0, 0);
+
+    let good_cases: Vec<(Location, usize)> = vec![
+        (Location::new(0, 3, 0), 3),
+        (Location::new(3, 0, 0), 3),
+        (Location::new(-6, -6, 0), 6),
+        (Location::new(-3, 6, 0), 6),
+    ];
+
+    for (loc, expected) in good_cases {
+        assert_eq!(reference_point.chebyshev_distance(&loc), expected);
+    }
+}
+
+#[test]
+fn test_euclidean_distance() {
+    let reference_point = Location::new(0, 0, 0);
+
+    let good_cases: Vec<(Location, f64)> = vec![
+        (Location::new(0, 3, 0), 3.0),
+        (Location::new(3, 0, 0), 3.0),
+        (Location::new(-6, -6, 0), 72f64.sqrt()),
+        (Location::new(-3, 6, 0), 45f64.sqrt()),
+        (Location::new(3, -4, 0), 5.0),
+    ];
+
+    for (loc, expected) in good_cases {
+        assert!((reference_point.euclidean_distance(&loc) - expected).abs() < f64::EPSILON);
+    }
+}
+
+#[test]
+fn test_location_operators() {
+    let start = Location::new(3, -2, 10);
+    let movement = Location::new(-5, 4, 9);
+
+    assert_eq!(start.clone() + movement.clone(), Location::new(-2, 2, 19));
+    assert_eq!((start.clone() + movement.clone()) - start.clone(), movement);
+
+    // Distances can't become negative
+    assert_eq!(
+        start.clone() - Location::new(1, 1, 25),
+        Location::new(2, -3, 0)
+    );
+
+    assert_eq!(-start, Location::new(-3, 2, 10));
+}
+
+#[test]
+fn test_point_conversions() {
+    let loc = Location::from(Point::new(-4, 7));
+    assert_eq!(loc, Location::new(-4, 7, 0));
+
+    // Converting back drops the distance but keeps the coordinates
+    let travelled = Location::new(12, -3, 40);
+    assert_eq!(Point::from(&travelled), Point::new(12, -3));
+    assert_eq!(
+        Location::from(Point::from(&travelled)),
+        Location::new(12, -3, 0)
+    );
+}
+
+#[test]
+fn test_position_key_dedup() {
+    let locations = [
+        Location::new(3, 3, 20),
+        Location::new(6, 5, 30),
+        Location::new(3, 3, 40),
+        Location::new(-2, 4, 12),
+        Location::new(6, 5, 30),
+    ];
+
+    let unique: HashSet<PositionKey> = locations.iter().map(PositionKey::from).collect();
+    assert_eq!(unique.len(), 3);
+    assert!(unique.contains(&PositionKey(3, 3)));
+    assert!(unique.contains(&PositionKey(-2, 4)));
+
+    // The distances still make the locations themselves distinct
+    assert_ne!(locations[0], locations[2]);
+    assert_eq!(locations[0].position_key(), locations[2].position_key());
+
+    // Which means they collide when only their positions are kept
+    let pair: HashSet<PositionKey> = [&locations[0], &locations[2]]
+        .iter()
+        .map(|l| l.position_key())
+        .collect();
+    assert_eq!(pair.len(), 1);
+}
+
+#[test]
+fn test_parse_directions_errors() {
+    let err = parse_directions("R8,U5,Q3").unwrap_err();
+    assert!(err.starts_with("token 2 ('Q3') invalid: "), "{}", err);
+
+    let err = parse_directions("R8,Ux").unwrap_err();
+    assert!(err.starts_with("token 1 ('Ux') invalid: "), "{}", err);
+}
+
+#[test]
+fn test_directions_round_trip() {
+    let samples = [
+        "R8,U5,L5,D3",
+        "U7,R6,D4,L4",
+        "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+        "U62,R66,U55,R34,D71,R55,D58,R83",
+        "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+        "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+    ];
+
+    for sample in samples.iter() {
+        let dirs = parse_directions(sample).unwrap();
+        assert_eq!(&directions_to_string(&dirs), sample);
+    }
+
+    assert_eq!(Direction::Right(8).to_string(), "R8");
+    assert_eq!(directions_to_string(&[]), "");
+}
+
+#[test]
+fn test_coord_key_collisions() {
+    let first = Location::new(3, 3, 20);
+    let second = Location::new(3, 3, 40);
+
+    assert_ne!(first, second);
+    assert_eq!(first.coord_key(), second.coord_key());
+
+    let coords: HashSet<(isize, isize)> = [first, second].iter().map(|l| l.coord_key()).collect();
+    assert_eq!(coords.len(), 1);
+    assert!(coords.contains(&(3, 3)));
+}
+
+#[test]
+fn test_overlapping_positions() {
+    let origin = Location::new(0, 0, 0);
+    let first = relative_to_absolute(origin.clone(), &parse_directions("R8,U5,L5,D3").unwrap());
+    let second = relative_to_absolute(origin, &parse_directions("U7,R6,D4,L4").unwrap());
+
+    let overlaps = overlapping_positions(&first, &second);
+    let expected: HashSet<PositionKey> = [PositionKey(0, 0), PositionKey(3, 3), PositionKey(6, 5)]
+        .iter()
+        .cloned()
+        .collect();
+
+    assert_eq!(overlaps, expected);
+}
+
 #[test]
 fn test_absolute_translation() {
     let good_cases: Vec<(Location, Direction, Location)> = vec![
@@ -75,6 +225,25 @@ fn test_series_of_absolute_translations() {
     );
 }
 
+#[test]
+fn test_direction_display_round_trip() {
+    let cases: Vec<(&'static str, Direction)> = vec![
+        ("D23", Direction::Down(23)),
+        ("L0", Direction::Left(0)),
+        ("R2", Direction::Right(2)),
+        ("U12384", Direction::Up(12384)),
+    ];
+
+    for (canonical, dir) in cases {
+        let rendered = dir.to_string();
+        assert_eq!(rendered, canonical);
+
+        let parsed = Direction::from_str(&rendered).unwrap();
+        assert_eq!(parsed.to_string(), rendered);
+        assert_eq!(parsed, dir);
+    }
+}
+
 #[test]
 fn test_individual_direction() {
     let good_cases: Vec<(&'static str, Direction)> = vec![
@@ -85,7 +254,7 @@ fn test_individual_direction() {
     ];
 
     for (input, expected) in good_cases {
-        assert_eq!(Direction::from_str(&input), Ok(expected));
+        assert_eq!(Direction::from_str(input), Ok(expected));
     }
 }
 
@@ -113,11 +282,34 @@ fn test_parsing_directions() {
     ];
 
     for (input, expected) in cases {
-        let result = parse_directions(&input).unwrap();
+        let result = parse_directions(input).unwrap();
         assert_eq!(result, expected);
     }
 }
 
+#[test]
+fn test_normalizing_directions() {
+    let cases: Vec<(&'static str, Vec<Direction>)> = vec![
+        ("R5,L3", vec![Direction::Right(2)]),
+        ("R5,L5", vec![]),
+        ("U2,R5,L5,U3,D1", vec![Direction::Up(4)]),
+        (
+            "R8,U5,L5,D3",
+            vec![
+                Direction::Right(8),
+                Direction::Up(5),
+                Direction::Left(5),
+                Direction::Down(3),
+            ],
+        ),
+    ];
+
+    for (input, expected) in cases {
+        let directions = parse_directions(input).unwrap();
+        assert_eq!(normalize_directions(&directions), expected);
+    }
+}
+
 #[test]
 fn test_location_orientation() {
     let cases: Vec<(Location, Location, Location, Orientation)> = vec![
@@ -154,10 +346,7 @@ fn test_location_orientation() {
     ];
 
     for (p1, p2, p3, orientation) in cases {
-        assert_eq!(
-            Orientation::from_three_locations(&p1, &p2, &p3),
-            orientation
-        );
+        assert_eq!(Orientation::from_three_points(&p1, &p2, &p3), orientation);
     }
 }
 
@@ -295,6 +484,29 @@ fn test_location_set_to_line_set() {
     assert_eq!(location_set_to_line_set(location_set), line_set);
 }
 
+#[test]
+fn test_position_at_step() {
+    let directions = parse_directions("R8,U5,L5,D3").unwrap();
+    let segments =
+        location_set_to_line_set(relative_to_absolute(Location::new(0, 0, 0), &directions));
+
+    let cases: Vec<(usize, Option<Location>)> = vec![
+        (0, Some(Location::new(0, 0, 0))),
+        (3, Some(Location::new(3, 0, 3))),
+        (8, Some(Location::new(8, 0, 8))),
+        (10, Some(Location::new(8, 2, 10))),
+        (15, Some(Location::new(6, 5, 15))),
+        (21, Some(Location::new(3, 2, 21))),
+        (22, None),
+    ];
+
+    for (step, expected) in cases {
+        assert_eq!(position_at_step(&segments, step), expected);
+    }
+
+    assert_eq!(position_at_step(&[], 0), None);
+}
+
 #[test]
 fn test_line_segment_intersection_calculation() {
     let cases: Vec<(
@@ -351,3 +563,183 @@ fn test_line_segment_intersection_calculation() {
         assert_eq!(line_seg1.intersecting_location(&line_seg2), result);
     }
 }
+
+#[test]
+fn test_wire_analysis() {
+    let cases: Vec<(&'static str, &'static str, WireAnalysis)> = vec![
+        (
+            "R8,U5,L5,D3",
+            "U7,R6,D4,L4",
+            WireAnalysis {
+                nearest_manhattan: Some(6),
+                fewest_steps: Some(30),
+                intersection_count: 2,
+            },
+        ),
+        (
+            "R75,D30,R83,U83,L12,D49,R71,U7,L72",
+            "U62,R66,U55,R34,D71,R55,D58,R83",
+            WireAnalysis {
+                nearest_manhattan: Some(159),
+                fewest_steps: Some(610),
+                intersection_count: 4,
+            },
+        ),
+        (
+            "R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51",
+            "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7",
+            WireAnalysis {
+                nearest_manhattan: Some(135),
+                fewest_steps: Some(410),
+                intersection_count: 5,
+            },
+        ),
+    ];
+
+    for (first, second, expected) in cases {
+        let origin = Location::new(0, 0, 0);
+        let first_lines = location_set_to_line_set(relative_to_absolute(
+            origin.clone(),
+            &parse_directions(first).unwrap(),
+        ));
+        let second_lines = location_set_to_line_set(relative_to_absolute(
+            origin,
+            &parse_directions(second).unwrap(),
+        ));
+
+        // The standalone helpers agree with the combined analysis
+        let intersections = find_intersections(&first_lines, &second_lines);
+        assert_eq!(
+            closest_by_manhattan(&intersections, &Location::new(0, 0, 0)),
+            expected.nearest_manhattan
+        );
+        assert_eq!(closest_by_steps(&intersections), expected.fewest_steps);
+
+        assert_eq!(analyze_wires(&first_lines, &second_lines), expected);
+    }
+
+    // The origin alone doesn't count for either
+    let origin = Location::new(0, 0, 0);
+    let only_origin = [origin.clone()];
+    assert_eq!(closest_by_manhattan(&only_origin, &origin), None);
+    assert_eq!(closest_by_steps(&only_origin), None);
+
+    // Distances are measured from whichever origin is provided, and it's that point that gets
+    // ignored rather than wherever the wires started
+    let intersections = [origin, Location::new(3, 4, 20), Location::new(-2, 1, 10)];
+    assert_eq!(
+        closest_by_manhattan(&intersections, &Location::new(3, 4, 0)),
+        Some(7)
+    );
+    assert_eq!(closest_by_steps(&intersections), Some(10));
+}
+
+#[test]
+fn test_render_grid() {
+    let origin = Location::new(0, 0, 0);
+    let first = relative_to_absolute(origin.clone(), &parse_directions("R8,U5,L5,D3").unwrap());
+    let second = relative_to_absolute(origin.clone(), &parse_directions("U7,R6,D4,L4").unwrap());
+
+    let expected = [
+        "...........",
+        ".+-----+...",
+        ".|.....|...",
+        ".|..+--X-+.",
+        ".|..|..|.|.",
+        ".|.-X--+.|.",
+        ".|..|....|.",
+        ".|.......|.",
+        ".o-------+.",
+        "...........",
+    ];
+    assert_eq!(render_grid(&first, &second), expected.join("\n"));
+
+    // Negative coordinates are shifted onto the grid
+    let negative = relative_to_absolute(origin.clone(), &parse_directions("L2,D1").unwrap());
+    let expected = [".....", ".+-o.", ".|...", "....."];
+    assert_eq!(render_grid(&negative, &[]), expected.join("\n"));
+
+    // Without any wires there is only the origin left to draw
+    assert_eq!(render_grid(&[], &[]), ["...", ".o.", "..."].join("\n"));
+
+    let huge = relative_to_absolute(origin, &parse_directions("R500,U2").unwrap());
+    assert_eq!(
+        render_grid(&huge, &[]),
+        "wires cover 501x3 cells which is larger than the 200x200 limit"
+    );
+}
+
+#[test]
+fn test_wires_bounding_box() {
+    let origin = Location::new(0, 0, 0);
+    let first = relative_to_absolute(origin.clone(), &parse_directions("R8,U5,L5,D3").unwrap());
+    let second = relative_to_absolute(origin.clone(), &parse_directions("U7,R6,D4,L4").unwrap());
+
+    assert_eq!(
+        wires_bounding_box(&[&first, &second]),
+        (Location::new(0, 0, 0), Location::new(8, 7, 0))
+    );
+
+    let negative = relative_to_absolute(origin, &parse_directions("L3,D4").unwrap());
+    assert_eq!(
+        wires_bounding_box(&[&first, &negative]),
+        (Location::new(-3, -4, 0), Location::new(8, 5, 0))
+    );
+
+    assert_eq!(
+        wires_bounding_box(&[]),
+        (Location::new(0, 0, 0), Location::new(0, 0, 0))
+    );
+    assert_eq!(
+        wires_bounding_box(&[&[]]),
+        (Location::new(0, 0, 0), Location::new(0, 0, 0))
+    );
+}
+
+#[test]
+fn test_overlap_points() {
+    let first = LineSegment(Location::new(0, 2, 0), Location::new(6, 2, 6));
+    let second = LineSegment(Location::new(8, 2, 10), Location::new(3, 2, 15));
+
+    assert_eq!(
+        overlap_points(&first, &second),
+        vec![
+            Location::new(3, 2, 18),
+            Location::new(4, 2, 18),
+            Location::new(5, 2, 18),
+            Location::new(6, 2, 18),
+        ]
+    );
+
+    // Parallel but offset segments share nothing
+    let offset = LineSegment(Location::new(0, 3, 0), Location::new(6, 3, 6));
+    assert!(overlap_points(&first, &offset).is_empty());
+
+    // Colinear segments that don't reach each other share nothing either
+    let distant = LineSegment(Location::new(7, 2, 0), Location::new(9, 2, 2));
+    assert!(overlap_points(&first, &distant).is_empty());
+
+    // Crossing segments aren't colinear, those are handled by `intersecting_location()`
+    let crossing = LineSegment(Location::new(3, 0, 0), Location::new(3, 5, 5));
+    assert!(overlap_points(&first, &crossing).is_empty());
+}
+
+#[test]
+fn test_crossing_kind() {
+    let horizontal = LineSegment(Location::new(0, 2, 0), Location::new(6, 2, 6));
+
+    let vertical = LineSegment(Location::new(3, 0, 0), Location::new(3, 5, 5));
+    assert_eq!(
+        crossing_kind(&horizontal, &vertical),
+        CrossingKind::Transverse(Location::new(3, 2, 5))
+    );
+
+    let shared_track = LineSegment(Location::new(5, 2, 0), Location::new(9, 2, 4));
+    assert_eq!(
+        crossing_kind(&horizontal, &shared_track),
+        CrossingKind::Overlap(vec![Location::new(5, 2, 5), Location::new(6, 2, 7)])
+    );
+
+    let distant = LineSegment(Location::new(8, 0, 0), Location::new(8, 5, 5));
+    assert_eq!(crossing_kind(&horizontal, &distant), CrossingKind::None);
+}
@@ -0,0 +1,34 @@
+//! Compares checking every password in the range individually, which splits each value into its
+//! digits on its own, against validating the range once up front and only looking at the values
+//! that could possibly pass. The digit DP counter, which never looks at individual values at all,
+//! is included for comparison. Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use day_04::{check_numeric_rules, count_valid_fast, count_valid_in_range};
+
+const START: usize = 153_517;
+const END: usize = 630_395;
+
+fn bench_password_rules(c: &mut Criterion) {
+    let mut group = c.benchmark_group("password_rules");
+
+    group.bench_function("checked_per_value", |b| {
+        b.iter(|| {
+            (START..END)
+                .filter(|num| check_numeric_rules(*num).unwrap())
+                .count()
+        })
+    });
+
+    group.bench_function("validated_range", |b| {
+        b.iter(|| count_valid_in_range(START..END, false).unwrap())
+    });
+
+    group.bench_function("digit_dp", |b| b.iter(|| count_valid_fast(START..END)));
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_password_rules);
+criterion_main!(benches);
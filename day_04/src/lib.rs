@@ -0,0 +1,659 @@
+use std::cmp;
+use std::ops::Range;
+
+/// The passwords are all six digit numbers, this is the range of values that covers.
+const SIX_DIGIT_RANGE: Range<usize> = 100_000..1_000_000;
+
+/// Checks a password against the basic rules. The puzzle only deals with six digit passwords but
+/// the rules make sense for any number of digits, so any positive number is accepted.
+pub fn check_numeric_rules(num: usize) -> Result<bool, &'static str> {
+    Ok(follows_basic_rules(&positive_digits(num)?))
+}
+
+/// Checks the digits against the rules defined in the first part of the puzzle. The digits must
+/// never decrease from left to right and at least two adjacent digits must be the same.
+fn follows_basic_rules(num_list: &[u8]) -> bool {
+    follows_all(num_list, &[&NonDecreasing, &HasAdjacentPair])
+}
+
+/// Checks a password against the extended rules, like `check_numeric_rules()` this accepts any
+/// positive number.
+pub fn check_extended_numeric_rules(num: usize) -> Result<bool, &'static str> {
+    Ok(follows_extended_rules(&positive_digits(num)?))
+}
+
+/// Splits up a password for the rule checks, which aren't defined for zero.
+fn positive_digits(num: usize) -> Result<Vec<u8>, &'static str> {
+    if num == 0 {
+        return Err("passwords must be positive numbers");
+    }
+
+    Ok(split_digits(num))
+}
+
+/// Checks the digits against the rules as extended by the second part of the puzzle. The digits
+/// must still never decrease but there must now be a group of exactly two matching adjacent
+/// digits that isn't part of a larger group.
+fn follows_extended_rules(num_list: &[u8]) -> bool {
+    follows_all(num_list, &[&NonDecreasing, &HasExactRun(2)])
+}
+
+/// A single property a password's digits need to have. The puzzle's rules are each a combination
+/// of these, but they can be combined in any other way with `check_all()` as well.
+pub trait DigitRule {
+    fn check(&self, digits: &[u8]) -> bool;
+}
+
+/// The digits never decrease going from left to right.
+pub struct NonDecreasing;
+
+impl DigitRule for NonDecreasing {
+    fn check(&self, digits: &[u8]) -> bool {
+        digits.windows(2).all(|pair| pair[0] <= pair[1])
+    }
+}
+
+/// At least two adjacent digits are the same, it doesn't matter if they're part of a larger
+/// group.
+pub struct HasAdjacentPair;
+
+impl DigitRule for HasAdjacentPair {
+    fn check(&self, digits: &[u8]) -> bool {
+        digits.windows(2).any(|pair| pair[0] == pair[1])
+    }
+}
+
+/// There is a group of repeated digits that is exactly this long, it can't be part of a larger
+/// group of the same digit.
+pub struct HasExactRun(pub usize);
+
+impl DigitRule for HasExactRun {
+    fn check(&self, digits: &[u8]) -> bool {
+        let mut run_length = 0;
+
+        for (i, digit) in digits.iter().enumerate() {
+            run_length += 1;
+
+            // Only check the length once we've reached the end of the run
+            if digits.get(i + 1) != Some(digit) {
+                if run_length == self.0 {
+                    return true;
+                }
+
+                run_length = 0;
+            }
+        }
+
+        false
+    }
+}
+
+fn follows_all(digits: &[u8], rules: &[&dyn DigitRule]) -> bool {
+    rules.iter().all(|rule| rule.check(digits))
+}
+
+/// Checks a six digit password against every one of the provided rules, passing only if all of
+/// them do.
+pub fn check_all(num: usize, rules: &[&dyn DigitRule]) -> Result<bool, String> {
+    Ok(follows_all(&split_numeric(num)?, rules))
+}
+
+/// Applies either the basic or extended password rules to the digits of a number written out in
+/// the provided radix. The rules don't actually care what base the digits are in, only how they
+/// compare to their neighbours, so for base 10 this gives the same answers as the other checks.
+/// Unlike those this accepts any number of digits.
+pub fn check_rules_radix(num: u64, radix: u32, extended: bool) -> Result<bool, String> {
+    let digits = digits_in_radix(num, radix)?;
+
+    if extended {
+        Ok(follows_extended_rules(&digits))
+    } else {
+        Ok(follows_basic_rules(&digits))
+    }
+}
+
+/// Applies either the basic or extended password rules to a password provided as a string of
+/// digits. Unlike the numeric checks this accepts any number of digits and doesn't lose any
+/// leading zeros the password may have.
+pub fn check_str_rules(password: &str, extended: bool) -> Result<bool, String> {
+    let digits = digits_from_str(password)?;
+
+    if extended {
+        Ok(follows_extended_rules(&digits))
+    } else {
+        Ok(follows_basic_rules(&digits))
+    }
+}
+
+/// Breaks a string of decimal digits up into the individual digit values, preserving any leading
+/// zeros.
+pub fn digits_from_str(s: &str) -> Result<Vec<u8>, String> {
+    let trimmed = s.trim();
+
+    if trimmed.is_empty() {
+        return Err("no digits were provided".to_string());
+    }
+
+    trimmed
+        .chars()
+        .map(|c| match c.to_digit(10) {
+            Some(d) => Ok(d as u8),
+            None => Err(format!("`{}` is not a valid digit", c)),
+        })
+        .collect()
+}
+
+/// Breaks a number up into its individual digits in the provided radix, most significant digit
+/// first. Only radixes between 2 and 36 are supported matching the standard library's own
+/// character based conversions.
+pub fn digits_in_radix(num: u64, radix: u32) -> Result<Vec<u8>, String> {
+    if !(2..=36).contains(&radix) {
+        return Err(format!("radix `{}` must be between 2 and 36", radix));
+    }
+
+    let radix = radix as u64;
+    let mut remaining = num;
+    let mut digits = Vec::new();
+
+    loop {
+        digits.push((remaining % radix) as u8);
+        remaining /= radix;
+
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    digits.reverse();
+    Ok(digits)
+}
+
+/// Counts the passwords in the range that satisfy either the basic or extended rules. The range
+/// is validated once up front allowing each password within it to skip the range check that
+/// `split_numeric` would otherwise perform for every value.
+pub fn count_valid_in_range(range: Range<usize>, extended: bool) -> Result<usize, &'static str> {
+    if range.is_empty() {
+        return Ok(0);
+    }
+
+    if !SIX_DIGIT_RANGE.contains(&range.start) || !SIX_DIGIT_RANGE.contains(&(range.end - 1)) {
+        return Err("range extends outside of six digit values");
+    }
+
+    if extended {
+        Ok(count_matching(range, follows_extended_rules))
+    } else {
+        Ok(count_matching(range, follows_basic_rules))
+    }
+}
+
+/// Counts the six digit passwords in the range that follow the basic rules without looking at any
+/// of them individually. Instead this works through the digits of the range's bounds one position
+/// at a time, counting how many ways the remaining digits can be filled in (a digit DP). Values in
+/// the range that aren't six digits long are never counted, so this agrees with
+/// `count_valid_in_range()` wherever that accepts the range.
+pub fn count_valid_fast(range: Range<usize>) -> usize {
+    if range.is_empty() {
+        return 0;
+    }
+
+    let below_start = match range.start.checked_sub(1) {
+        Some(val) => count_valid_up_to(val),
+        None => 0,
+    };
+
+    count_valid_up_to(range.end - 1) - below_start
+}
+
+/// The number of six digit values up to and including the bound that follow the basic rules.
+fn count_valid_up_to(bound: usize) -> usize {
+    if bound < SIX_DIGIT_RANGE.start {
+        return 0;
+    }
+
+    let bound = cmp::min(bound, SIX_DIGIT_RANGE.end - 1);
+    let digits = check_range_digits_unchecked(bound);
+
+    // Once a prefix is below the bound the count of ways to finish it only depends on the position,
+    // the last digit, and whether a pair has been seen yet, so those are worth remembering
+    let mut memo = [[[None; 2]; 10]; 6];
+
+    count_completions(&digits, 0, 0, false, true, &mut memo)
+}
+
+fn count_completions(
+    bound: &[u8; 6],
+    pos: usize,
+    last: u8,
+    has_pair: bool,
+    tight: bool,
+    memo: &mut [[[Option<usize>; 2]; 10]; 6],
+) -> usize {
+    if pos == bound.len() {
+        return has_pair as usize;
+    }
+
+    if !tight {
+        if let Some(count) = memo[pos][last as usize][has_pair as usize] {
+            return count;
+        }
+    }
+
+    // The first digit can't be zero, after that digits can never be smaller than the last one
+    let lowest = if pos == 0 { 1 } else { last };
+    let highest = if tight { bound[pos] } else { 9 };
+
+    let mut count = 0;
+    for digit in lowest..=highest {
+        let pair = has_pair || (pos > 0 && digit == last);
+        count += count_completions(bound, pos + 1, digit, pair, tight && digit == highest, memo);
+    }
+
+    if !tight {
+        memo[pos][last as usize][has_pair as usize] = Some(count);
+    }
+
+    count
+}
+
+fn count_matching(range: Range<usize>, rules: impl Fn(&[u8]) -> bool) -> usize {
+    // Both sets of rules reject anything with decreasing digits, so there is no point in checking
+    // the vast majority of the range
+    non_decreasing_in_range(range)
+        .filter(|num| rules(&check_range_digits_unchecked(*num)))
+        .count()
+}
+
+/// Produces only the six digit numbers in the range whose digits never decrease, in ascending
+/// order. Rather than scanning the range and rejecting values, each candidate is built directly
+/// from the previous one, so only a few thousand values are ever produced no matter how large the
+/// range is. Values in the range that aren't six digits long are never produced.
+pub fn non_decreasing_in_range(range: Range<usize>) -> impl Iterator<Item = usize> {
+    let Range { start, end } = range;
+
+    std::iter::successors(Some([1u8; 6]), next_non_decreasing)
+        .map(|digits| digits.iter().fold(0, |num, d| num * 10 + *d as usize))
+        .skip_while(move |num| *num < start)
+        .take_while(move |num| *num < end)
+}
+
+/// Finds the next largest set of non-decreasing digits. This works just like counting, except
+/// when a digit rolls over everything after it is reset to the new value of that digit rather
+/// than zero, as anything smaller would be a decrease.
+fn next_non_decreasing(digits: &[u8; 6]) -> Option<[u8; 6]> {
+    let pos = digits.iter().rposition(|d| *d < 9)?;
+    let mut next = *digits;
+
+    let value = next[pos] + 1;
+    for digit in next[pos..].iter_mut() {
+        *digit = value;
+    }
+
+    Some(next)
+}
+
+/// The same as `split_numeric` but without the range check. This is only intended for use on
+/// values that are already known to be six digits long, which is only asserted in debug builds.
+fn check_range_digits_unchecked(num: usize) -> [u8; 6] {
+    debug_assert!(SIX_DIGIT_RANGE.contains(&num));
+
+    [
+        (num / 100_000) as u8,
+        (num / 10_000 % 10) as u8,
+        (num / 1_000 % 10) as u8,
+        (num / 100 % 10) as u8,
+        (num / 10 % 10) as u8,
+        (num % 10) as u8,
+    ]
+}
+
+/// Parses the puzzle input, which is the range to search written as `low-high`. The high value is
+/// treated as the exclusive end of the range, the puzzle doesn't say whether it is included but
+/// it never makes a difference with real inputs.
+pub fn parse_range(s: &str) -> Result<Range<usize>, String> {
+    let mut sides = s.trim().splitn(2, '-');
+
+    let mut parse_side = |name: &str| -> Result<usize, String> {
+        let side = sides.next().unwrap_or("").trim();
+        side.parse()
+            .map_err(|err| format!("{} end of the range `{}` is invalid: {}", name, side, err))
+    };
+
+    let low = parse_side("low")?;
+    let high = parse_side("high")?;
+
+    if low > high {
+        return Err(format!("range {}-{} is inverted", low, high));
+    }
+
+    Ok(low..high)
+}
+
+/// Produces every password in the range that satisfies either the basic or extended rules, in
+/// ascending order. Unlike `count_valid_in_range()` this checks every value individually, so the
+/// range isn't limited to six digit values, but it is a lot slower over large ranges.
+pub fn valid_passwords(range: Range<usize>, extended: bool) -> impl Iterator<Item = usize> {
+    range.filter(move |num| {
+        let result = if extended {
+            check_extended_numeric_rules(*num)
+        } else {
+            check_numeric_rules(*num)
+        };
+
+        result.unwrap_or(false)
+    })
+}
+
+/// Breaks a number of any length up into its decimal digits, most significant digit first.
+pub fn split_digits(num: usize) -> Vec<u8> {
+    let mut remaining = num;
+    let mut digits = Vec::new();
+
+    loop {
+        digits.push((remaining % 10) as u8);
+        remaining /= 10;
+
+        if remaining == 0 {
+            break;
+        }
+    }
+
+    digits.reverse();
+    digits
+}
+
+pub fn split_numeric(num: usize) -> Result<[u8; 6], &'static str> {
+    // We can only handle six digit numbers
+    if !SIX_DIGIT_RANGE.contains(&num) {
+        return Err("value is outside the correct range");
+    }
+
+    Ok(check_range_digits_unchecked(num))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numeric_rule_checker() {
+        assert!(check_numeric_rules(111_111).unwrap());
+        assert!(!check_numeric_rules(223_450).unwrap());
+        assert!(!check_numeric_rules(123_789).unwrap());
+
+        // Other lengths follow the same rules
+        assert!(check_numeric_rules(1_123).unwrap());
+        assert!(!check_numeric_rules(1_234).unwrap());
+        assert!(!check_numeric_rules(1_000).unwrap());
+        assert!(check_numeric_rules(11_223_344).unwrap());
+        assert!(!check_numeric_rules(1_000_000).unwrap());
+
+        assert!(check_numeric_rules(0).is_err());
+    }
+
+    #[test]
+    fn test_extended_numeric_rule_checker() {
+        assert!(check_extended_numeric_rules(112_233).unwrap());
+        assert!(check_extended_numeric_rules(111_122).unwrap());
+
+        assert!(!check_extended_numeric_rules(111_111).unwrap());
+        assert!(!check_extended_numeric_rules(123_444).unwrap());
+        assert!(!check_extended_numeric_rules(223_450).unwrap());
+        assert!(!check_extended_numeric_rules(123_789).unwrap());
+
+        assert!(check_extended_numeric_rules(1_123).unwrap());
+        assert!(!check_extended_numeric_rules(1_113).unwrap());
+        assert!(check_extended_numeric_rules(11_122_333).unwrap());
+        assert!(!check_extended_numeric_rules(22_223_333).unwrap());
+
+        assert!(check_extended_numeric_rules(0).is_err());
+    }
+
+    #[test]
+    fn test_digit_rules() {
+        assert!(NonDecreasing.check(&[1, 1, 2, 3, 9]));
+        assert!(!NonDecreasing.check(&[1, 2, 1]));
+
+        assert!(HasAdjacentPair.check(&[1, 2, 2, 2]));
+        assert!(!HasAdjacentPair.check(&[1, 2, 1, 2]));
+
+        assert!(HasExactRun(2).check(&[1, 1, 1, 1, 2, 2]));
+        assert!(!HasExactRun(2).check(&[1, 2, 2, 2, 3]));
+        assert!(HasExactRun(3).check(&[1, 2, 2, 2, 3]));
+        assert!(HasExactRun(1).check(&[1, 1, 2]));
+        assert!(!HasExactRun(1).check(&[1, 1, 2, 2]));
+    }
+
+    #[test]
+    fn test_composed_rules() {
+        let extended: [&dyn DigitRule; 2] = [&NonDecreasing, &HasExactRun(2)];
+
+        for num in (100_000..1_000_000).step_by(31) {
+            assert_eq!(
+                check_all(num, &extended).unwrap(),
+                check_extended_numeric_rules(num).unwrap()
+            );
+        }
+
+        assert!(check_all(112_233, &extended).unwrap());
+        assert!(!check_all(123_444, &extended).unwrap());
+        assert!(check_all(1_000, &extended).is_err());
+    }
+
+    #[test]
+    fn test_digits_from_str() {
+        assert_eq!(digits_from_str("001234").unwrap(), vec![0, 0, 1, 2, 3, 4]);
+        assert_eq!(digits_from_str("153517").unwrap(), vec![1, 5, 3, 5, 1, 7]);
+
+        assert!(digits_from_str("").is_err());
+        assert!(digits_from_str("12a456").is_err());
+        assert!(digits_from_str("-12345").is_err());
+    }
+
+    #[test]
+    fn test_str_rule_checker() {
+        assert!(check_str_rules("001234", false).unwrap());
+        assert!(check_str_rules("001234", true).unwrap());
+        assert!(!check_str_rules("000123", true).unwrap());
+        assert!(check_str_rules("111122", true).unwrap());
+
+        assert!(check_str_rules("12x", false).is_err());
+    }
+
+    #[test]
+    fn test_digits_in_radix() {
+        assert_eq!(digits_in_radix(0, 10).unwrap(), vec![0]);
+        assert_eq!(
+            digits_in_radix(153_517, 10).unwrap(),
+            vec![1, 5, 3, 5, 1, 7]
+        );
+        assert_eq!(
+            digits_in_radix(0x11_abcf, 16).unwrap(),
+            vec![1, 1, 10, 11, 12, 15]
+        );
+        assert_eq!(digits_in_radix(5, 2).unwrap(), vec![1, 0, 1]);
+
+        assert!(digits_in_radix(10, 1).is_err());
+        assert!(digits_in_radix(10, 37).is_err());
+    }
+
+    #[test]
+    fn test_radix_rule_checker() {
+        // Base 10 needs to agree with the existing checks everywhere they're defined
+        for num in (100_000..1_000_000).step_by(997) {
+            assert_eq!(
+                check_rules_radix(num as u64, 10, false).unwrap(),
+                check_numeric_rules(num).unwrap()
+            );
+            assert_eq!(
+                check_rules_radix(num as u64, 10, true).unwrap(),
+                check_extended_numeric_rules(num).unwrap()
+            );
+        }
+
+        assert!(check_rules_radix(0x11_abcf, 16, false).unwrap());
+        assert!(check_rules_radix(0x11_abcf, 16, true).unwrap());
+
+        assert!(check_rules_radix(0x11_1def, 16, false).unwrap());
+        assert!(!check_rules_radix(0x11_1def, 16, true).unwrap());
+
+        assert!(!check_rules_radix(0xff_a000, 16, false).unwrap());
+        assert!(!check_rules_radix(0x12_3abc, 16, false).unwrap());
+
+        assert!(check_rules_radix(0x11_abcf, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_range_counting() {
+        let range = 153_517..160_000;
+
+        let basic = range.clone().filter(|n| check_numeric_rules(*n).unwrap());
+        assert_eq!(
+            count_valid_in_range(range.clone(), false),
+            Ok(basic.count())
+        );
+
+        let extended = range
+            .clone()
+            .filter(|n| check_extended_numeric_rules(*n).unwrap());
+        assert_eq!(count_valid_in_range(range, true), Ok(extended.count()));
+
+        assert_eq!(count_valid_in_range(111_111..111_112, false), Ok(1));
+        assert_eq!(count_valid_in_range(200_000..200_000, false), Ok(0));
+        assert!(count_valid_in_range(99_999..100_010, false).is_err());
+        assert!(count_valid_in_range(999_990..1_000_001, false).is_err());
+    }
+
+    #[test]
+    fn test_non_decreasing_generation() {
+        let candidates: Vec<usize> = non_decreasing_in_range(SIX_DIGIT_RANGE).collect();
+
+        // Choosing six digits from 1-9 with repetition where order doesn't matter
+        assert_eq!(candidates.len(), 3003);
+        assert_eq!(candidates[..3], [111_111, 111_112, 111_113]);
+        assert_eq!(candidates[candidates.len() - 1], 999_999);
+
+        let naive: Vec<usize> = SIX_DIGIT_RANGE
+            .filter(|num| {
+                let digits = split_numeric(*num).unwrap();
+                digits.windows(2).all(|pair| pair[0] <= pair[1])
+            })
+            .collect();
+        assert_eq!(candidates, naive);
+
+        // Filtering the candidates has to agree with scanning the whole range
+        let range = 153_517..630_395;
+        for extended in [false, true].iter() {
+            let naive_count = range
+                .clone()
+                .filter(|num| {
+                    if *extended {
+                        check_extended_numeric_rules(*num).unwrap()
+                    } else {
+                        check_numeric_rules(*num).unwrap()
+                    }
+                })
+                .count();
+
+            assert_eq!(
+                count_valid_in_range(range.clone(), *extended).unwrap(),
+                naive_count
+            );
+        }
+
+        assert_eq!(non_decreasing_in_range(200_000..222_222).count(), 0);
+        assert_eq!(non_decreasing_in_range(199_999..222_223).count(), 2);
+    }
+
+    #[test]
+    fn test_unchecked_digits_match() {
+        for num in &[100_000, 123_456, 783_100, 999_999] {
+            assert_eq!(
+                check_range_digits_unchecked(*num),
+                split_numeric(*num).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("153517-630395"), Ok(153_517..630_395));
+        assert_eq!(parse_range(" 111111-111111\n"), Ok(111_111..111_111));
+
+        assert!(parse_range("630395-153517").is_err());
+        assert!(parse_range("153517-abc").is_err());
+        assert!(parse_range("x-630395").is_err());
+        assert!(parse_range("153517").is_err());
+    }
+
+    #[test]
+    fn test_valid_passwords() {
+        let basic: Vec<usize> = valid_passwords(111_220..111_231, false).collect();
+        assert_eq!(basic, (111_222..=111_229).collect::<Vec<_>>());
+
+        // A run of three ones on its own doesn't count as a pair
+        let extended: Vec<usize> = valid_passwords(111_220..111_231, true).collect();
+        assert_eq!(extended, (111_223..=111_229).collect::<Vec<_>>());
+
+        // Zero isn't a valid password but it shouldn't stop anything else being found
+        let short: Vec<usize> = valid_passwords(0..12, false).collect();
+        assert_eq!(short, vec![11]);
+
+        let range = 153_517..160_000;
+        assert_eq!(
+            valid_passwords(range.clone(), false).count(),
+            count_valid_in_range(range, false).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fast_counting() {
+        let range = 111_111..222_222;
+        let brute_force = range
+            .clone()
+            .filter(|num| check_numeric_rules(*num).unwrap())
+            .count();
+        assert_eq!(count_valid_fast(range), brute_force);
+
+        assert_eq!(count_valid_fast(153_517..630_395), 1729);
+        assert_eq!(
+            count_valid_fast(153_517..160_000),
+            count_valid_in_range(153_517..160_000, false).unwrap()
+        );
+
+        // Single values, including either end of the six digit range
+        assert_eq!(count_valid_fast(111_111..111_112), 1);
+        assert_eq!(count_valid_fast(123_456..123_457), 0);
+        assert_eq!(count_valid_fast(999_999..1_000_000), 1);
+
+        // Anything outside of six digits is ignored
+        assert_eq!(count_valid_fast(0..100_000), 0);
+        assert_eq!(
+            count_valid_fast(0..2_000_000),
+            count_valid_fast(SIX_DIGIT_RANGE)
+        );
+        assert_eq!(count_valid_fast(200_000..200_000), 0);
+    }
+
+    #[test]
+    fn test_split_digits() {
+        assert_eq!(split_digits(0), vec![0]);
+        assert_eq!(split_digits(7), vec![7]);
+        assert_eq!(split_digits(1_123), vec![1, 1, 2, 3]);
+        assert_eq!(split_digits(123_456), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(split_digits(90_000_001), vec![9, 0, 0, 0, 0, 0, 0, 1]);
+
+        for num in (100_000..1_000_000).step_by(7_919) {
+            assert_eq!(split_digits(num), split_numeric(num).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_split_numeric() {
+        assert!(split_numeric(1_000).is_err());
+        assert!(split_numeric(1_000_000).is_err());
+
+        assert_eq!(split_numeric(123_456).unwrap(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(split_numeric(783_100).unwrap(), [7, 8, 3, 1, 0, 0]);
+    }
+}
@@ -1,31 +1,79 @@
-use std::fs::File;
-use std::io::Read;
-use std::str::FromStr;
+use common::AocError;
+use computer::{execute, load_program, Fault};
 
-use computer::IntCodeComputer;
+/// Runs the thermal environment supervision terminal's diagnostic program for the provided system
+/// ID and returns everything it output. Each of the outputs preceding the last one are the results
+/// of the individual tests and should be zero for a passing program, the final output is the
+/// diagnostic code itself.
+pub fn run_diagnostic(program: &str, system_id: isize) -> Result<Vec<isize>, Fault> {
+    execute(program, vec![system_id])
+}
+
+fn main() -> Result<(), AocError> {
+    let in_dat = load_program("./data/input.txt")?;
+
+    for (part, system_id) in [(1, 1), (2, 5)].iter() {
+        let output = run_diagnostic(&in_dat, *system_id)?;
+
+        let (diagnostic_code, test_results) = match output.split_last() {
+            Some(split) => split,
+            None => {
+                return Err(AocError::InvalidOutput(format!(
+                    "program part {} didn't produce a diagnostic code",
+                    part
+                )));
+            }
+        };
+
+        if test_results.iter().any(|r| *r != 0) {
+            return Err(AocError::InvalidOutput(format!(
+                "program part {} had failing tests: {:?}",
+                part, output
+            )));
+        }
+
+        println!("Diagnostic code for part {} was: {}", part, diagnostic_code);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type FaultResult = Result<(), Fault>;
+
+    #[test]
+    fn test_echo_diagnostic() -> FaultResult {
+        assert_eq!(run_diagnostic("3,0,4,0,99", 1)?, vec![1]);
+        assert_eq!(run_diagnostic("3,0,4,0,99", 5)?, vec![5]);
 
-fn main() {
-    let mut in_dat_fh = File::open("./data/input.txt").unwrap();
-    let mut in_dat = String::new();
+        Ok(())
+    }
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
-    let mut icc = IntCodeComputer::from_str(&in_dat).unwrap();
-    icc.add_input(vec![1]);
+    #[test]
+    fn test_comparison_diagnostic() -> FaultResult {
+        let sample_prog = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
 
-    if let Err(err) = icc.run() {
-        println!("Running the program encountered and error: {:?}", err);
-        std::process::exit(1);
-    };
+        assert_eq!(run_diagnostic(sample_prog, 1)?, vec![999]);
+        assert_eq!(run_diagnostic(sample_prog, 5)?, vec![999]);
+        assert_eq!(run_diagnostic(sample_prog, 8)?, vec![1000]);
+        assert_eq!(run_diagnostic(sample_prog, 9)?, vec![1001]);
 
-    println!("Output of program part 1 was: {:?}", icc.output());
+        Ok(())
+    }
 
-    icc.reset();
-    icc.add_input(vec![5]);
+    #[test]
+    fn test_diagnostic_output_shape() -> FaultResult {
+        // Reports two passing tests before outputting the system ID as the diagnostic code
+        let sample_prog = "3,9,104,0,104,0,4,9,99,0";
+        let output = run_diagnostic(sample_prog, 42)?;
 
-    if let Err(err) = icc.run() {
-        println!("Running the program encountered and error: {:?}", err);
-        std::process::exit(1);
-    };
+        let (diagnostic_code, test_results) = output.split_last().unwrap();
+        assert_eq!(*diagnostic_code, 42);
+        assert_eq!(test_results, &[0, 0]);
 
-    println!("Output of program part 2 was: {:?}", icc.output());
+        Ok(())
+    }
 }
@@ -1,11 +1,13 @@
 use std::fs::File;
 use std::io::Read;
 
-fn main() {
-    let mut in_dat_fh = File::open("./data/input.txt").unwrap();
+use common::AocError;
+
+fn main() -> Result<(), AocError> {
+    let mut in_dat_fh = File::open("./data/input.txt")?;
     let mut in_dat = String::new();
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
+    in_dat_fh.read_to_string(&mut in_dat)?;
 
     unimplemented!();
 }
@@ -1,33 +1,45 @@
-use std::fs::File;
-use std::io::Read;
+use std::io;
 use std::str::FromStr;
 
-use computer::{Fault, IntCodeComputer};
+use common::AocError;
+use computer::{load_program, Fault, IntCodeComputer};
 
 pub fn amplifier_chain(program: &str, settings: &[isize]) -> Result<isize, Fault> {
-    let mut icc = IntCodeComputer::from_str(&program)?;
-    let mut signal = 0;
+    let mut previous: Option<IntCodeComputer> = None;
 
-    for val in settings.into_iter() {
-        icc.reset();
-        icc.add_input(vec![*val, signal]);
-        icc.run()?;
+    for val in settings.iter() {
+        let mut icc = IntCodeComputer::from_str(program)?;
+        icc.add_input(vec![*val]);
+
+        // The first amplifier in the chain is the only one that doesn't receive its signal from
+        // the amplifier before it
+        match previous.as_mut() {
+            Some(prev) => prev.pipe_output_into(&mut icc),
+            None => icc.add_input(vec![0]),
+        }
 
-        signal = icc.output().into_iter().nth(0).unwrap();
+        icc.run()?;
+        previous = Some(icc);
     }
 
-    Ok(signal)
+    Ok(previous
+        .map(|mut icc| icc.output().into_iter().next().unwrap())
+        .unwrap_or(0))
 }
 
 pub fn amplifier_feedback_chain(program: &str, settings: &[isize]) -> Result<isize, Fault> {
+    if settings.is_empty() {
+        return Ok(0);
+    }
+
     let mut computers: Vec<IntCodeComputer> = settings
         .iter()
         .map(|init| {
-            let mut comp = IntCodeComputer::from_str(&program).unwrap();
+            let mut comp = IntCodeComputer::from_str(program)?;
             comp.add_input(vec![*init]);
-            comp
+            Ok(comp)
         })
-        .collect();
+        .collect::<Result<_, Fault>>()?;
 
     let last_computer_id = settings.len() - 1;
     let mut transfer_data: isize = 0;
@@ -36,7 +48,7 @@ pub fn amplifier_feedback_chain(program: &str, settings: &[isize]) -> Result<isi
     loop {
         computers[current_comp].add_input(vec![transfer_data]);
         computers[current_comp].run()?;
-        transfer_data = computers[current_comp].output().into_iter().nth(0).unwrap();
+        transfer_data = computers[current_comp].output().into_iter().next().unwrap();
 
         if current_comp == last_computer_id && computers[current_comp].is_halted() {
             break;
@@ -52,132 +64,89 @@ pub fn amplifier_feedback_chain(program: &str, settings: &[isize]) -> Result<isi
     Ok(transfer_data)
 }
 
-pub fn is_valid_setting(settings: &[isize]) -> bool {
-    // Must have length of 5
-    if settings.len() != 5 {
-        return false;
-    }
-
-    // Must contain each setting (and by proxy, contain it exactly once)
-    for i in 0..5 {
-        if settings.iter().find(|s| i as isize == **s).is_none() {
-            return false;
-        }
-    }
-
-    true
-}
+/// Tries every ordering of the provided phase values across a chain of amplifiers, one amplifier
+/// per phase, returning the largest signal that makes it out the other end. The puzzle uses the
+/// phases 0 through 4.
+pub fn find_maximum_output(program: &str, phase_values: &[isize]) -> Result<isize, Fault> {
+    let mut max_value = 0;
 
-pub fn is_valid_feedback_setting(settings: &[isize]) -> bool {
-    // Must have length of 5
-    if settings.len() != 5 {
-        return false;
-    }
+    for amplifier_settings in phase_permutations(phase_values) {
+        let new_value = amplifier_chain(program, &amplifier_settings)?;
 
-    // Must contain each setting (and by proxy, contain it exactly once)
-    for i in 5..10 {
-        if settings.iter().find(|s| i as isize == **s).is_none() {
-            return false;
+        if new_value > max_value {
+            max_value = new_value;
         }
     }
 
-    true
+    Ok(max_value)
 }
 
-pub fn find_maximum_output(program: &str) -> Result<isize, Fault> {
-    let mut amplifier_settings: [isize; 5] = [0; 5];
+/// The feedback loop equivalent of `find_maximum_output()`, the puzzle uses the phases 5 through 9.
+pub fn find_maximum_feedback_output(program: &str, phase_values: &[isize]) -> Result<isize, Fault> {
     let mut max_value = 0;
 
-    loop {
-        // We only calculate and update the chain if the settings are valid
-        if is_valid_setting(&amplifier_settings) {
-            let new_value = amplifier_chain(&program, &amplifier_settings)?;
+    for amplifier_settings in phase_permutations(phase_values) {
+        let new_value = amplifier_feedback_chain(program, &amplifier_settings)?;
 
-            if new_value > max_value {
-                max_value = new_value;
-            }
-        }
-
-        for pos in 0..5 {
-            amplifier_settings[pos] += 1;
-
-            if amplifier_settings[pos] > 4 {
-                // We're at the maximum value for the last position, return whatever we have
-                if pos == 4 {
-                    return Ok(max_value);
-                }
-
-                amplifier_settings[pos] = 0;
-            } else {
-                break;
-            }
+        if new_value > max_value {
+            max_value = new_value;
         }
     }
-}
-
-pub fn find_maximum_feedback_output(program: &str) -> Result<isize, Fault> {
-    let mut amplifier_settings: [isize; 5] = [5, 6, 7, 8, 9];
-    let mut max_value = 0;
 
-    loop {
-        // We only calculate and update the chain if the settings are valid
-        if is_valid_feedback_setting(&amplifier_settings) {
-            let new_value = amplifier_feedback_chain(&program, &amplifier_settings)?;
+    Ok(max_value)
+}
 
-            if new_value > max_value {
-                max_value = new_value;
+/// Generates every ordering of the provided phase values using Heap's algorithm. This is
+/// implemented here rather than pulling in `itertools` as the values are always distinct and the
+/// algorithm is short enough that the dependency isn't worth it.
+pub fn phase_permutations(values: &[isize]) -> Vec<Vec<isize>> {
+    let mut current = values.to_vec();
+    let mut permutations = vec![current.clone()];
+
+    // Each entry acts as the loop counter for the equivalent level of the recursive version of the
+    // algorithm.
+    let mut counters = vec![0; current.len()];
+    let mut idx = 0;
+
+    while idx < current.len() {
+        if counters[idx] < idx {
+            if idx % 2 == 0 {
+                current.swap(0, idx);
+            } else {
+                current.swap(counters[idx], idx);
             }
-        }
 
-        for pos in 0..5 {
-            amplifier_settings[pos] += 1;
+            permutations.push(current.clone());
 
-            if amplifier_settings[pos] > 10 {
-                // We're at the maximum value for the last position, return whatever we have
-                if pos == 4 {
-                    return Ok(max_value);
-                }
-
-                amplifier_settings[pos] = 5;
-            } else {
-                break;
-            }
+            counters[idx] += 1;
+            idx = 0;
+        } else {
+            counters[idx] = 0;
+            idx += 1;
         }
     }
-}
 
-pub fn get_program() -> String {
-    let mut in_dat_fh = File::open("./data/input.txt").unwrap();
-    let mut in_dat = String::new();
+    permutations
+}
 
-    in_dat_fh.read_to_string(&mut in_dat).unwrap();
-    in_dat
+pub fn get_program() -> io::Result<String> {
+    load_program("./data/input.txt")
 }
 
-fn main() {
-    let prog = get_program();
+fn main() -> Result<(), AocError> {
+    let prog = get_program()?;
 
-    let max_value = match find_maximum_output(&prog) {
-        Ok(val) => val,
-        Err(err) => {
-            println!("There was an error running the program: {:?}", err);
-            std::process::exit(1);
-        }
-    };
+    let max_value = find_maximum_output(&prog, &[0, 1, 2, 3, 4])?;
     println!("Maximum value for input program was: {}", max_value);
 
     // Got value 8320285 which was too low
-    let max_feedback_value = match find_maximum_feedback_output(&prog) {
-        Ok(val) => val,
-        Err(err) => {
-            println!("There was an error running the program: {:?}", err);
-            std::process::exit(1);
-        }
-    };
+    let max_feedback_value = find_maximum_feedback_output(&prog, &[5, 6, 7, 8, 9])?;
     println!(
         "Maximum feedback value for input program was: {}",
         max_feedback_value
     );
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -186,10 +155,84 @@ mod tests {
 
     type FaultResult = Result<(), computer::Fault>;
 
+    #[test]
+    fn test_phase_permutations() {
+        let mut permutations = phase_permutations(&[5, 6, 7, 8, 9]);
+        assert_eq!(permutations.len(), 120);
+
+        // Every ordering should be unique and contain each of the phases exactly once
+        permutations.sort();
+        permutations.dedup();
+        assert_eq!(permutations.len(), 120);
+
+        for perm in permutations {
+            let mut sorted = perm.clone();
+            sorted.sort();
+            assert_eq!(sorted, vec![5, 6, 7, 8, 9]);
+        }
+    }
+
+    #[test]
+    fn test_small_phase_permutations() {
+        let mut permutations = phase_permutations(&[0, 1, 2]);
+        permutations.sort();
+
+        assert_eq!(
+            permutations,
+            vec![
+                vec![0, 1, 2],
+                vec![0, 2, 1],
+                vec![1, 0, 2],
+                vec![1, 2, 0],
+                vec![2, 0, 1],
+                vec![2, 1, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_maximum_feedback_output() -> FaultResult {
+        let sample_prog =
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+        assert_eq!(
+            find_maximum_feedback_output(sample_prog, &[5, 6, 7, 8, 9])?,
+            139_629_729
+        );
+
+        let sample_prog = "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10";
+        assert_eq!(
+            find_maximum_feedback_output(sample_prog, &[5, 6, 7, 8, 9])?,
+            18_216
+        );
+
+        Ok(())
+    }
+
+    /// An end to end check of both parts against the actual puzzle input. The input is personal to
+    /// each account so this quietly passes when it hasn't been checked in.
+    #[test]
+    fn test_puzzle_input_answers() -> FaultResult {
+        let prog = match get_program() {
+            Ok(prog) => prog,
+            Err(err) => {
+                eprintln!("Skipping puzzle input test, input unavailable: {}", err);
+                return Ok(());
+            }
+        };
+
+        assert_eq!(find_maximum_output(&prog, &[0, 1, 2, 3, 4])?, 277_328);
+        assert_eq!(
+            find_maximum_feedback_output(&prog, &[5, 6, 7, 8, 9])?,
+            11_304_734
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_sample_program_chains1() -> FaultResult {
         let sample_prog = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
-        let output = amplifier_chain(&sample_prog, &vec![4, 3, 2, 1, 0])?;
+        let output = amplifier_chain(sample_prog, &[4, 3, 2, 1, 0])?;
         assert_eq!(output, 43210);
 
         Ok(())
@@ -199,7 +242,7 @@ mod tests {
     fn test_sample_program_chains2() -> FaultResult {
         let sample_prog =
             "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0";
-        let output = amplifier_chain(&sample_prog, &vec![0, 1, 2, 3, 4])?;
+        let output = amplifier_chain(sample_prog, &[0, 1, 2, 3, 4])?;
         assert_eq!(output, 54321);
 
         Ok(())
@@ -208,16 +251,41 @@ mod tests {
     #[test]
     fn test_sample_program_chains3() -> FaultResult {
         let sample_prog = "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0";
-        let output = amplifier_chain(&sample_prog, &vec![1, 0, 4, 3, 2])?;
+        let output = amplifier_chain(sample_prog, &[1, 0, 4, 3, 2])?;
         assert_eq!(output, 65210);
 
         Ok(())
     }
 
+    #[test]
+    fn test_other_amplifier_counts() -> FaultResult {
+        // Each amplifier shifts the signal up a digit and adds its phase, so the best ordering
+        // reads off the phases in descending order
+        let sample_prog = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
+
+        assert_eq!(amplifier_chain(sample_prog, &[2, 0, 1])?, 201);
+        assert_eq!(find_maximum_output(sample_prog, &[0, 1, 2])?, 210);
+        assert_eq!(
+            find_maximum_output(sample_prog, &[0, 1, 2, 3, 4, 5, 6])?,
+            6_543_210
+        );
+
+        // The feedback loop can be shorter as well, without any amplifiers nothing is ever sent
+        let feedback_prog =
+            "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
+        assert_eq!(find_maximum_feedback_output(feedback_prog, &[])?, 0);
+        assert_eq!(
+            find_maximum_feedback_output(feedback_prog, &[5, 6, 7])?,
+            79_577
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_find_maximum_output1() -> FaultResult {
         let sample_prog = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
-        let output = find_maximum_output(&sample_prog)?;
+        let output = find_maximum_output(sample_prog, &[0, 1, 2, 3, 4])?;
         assert_eq!(output, 43210);
 
         Ok(())
@@ -227,7 +295,7 @@ mod tests {
     fn test_find_maximum_output2() -> FaultResult {
         let sample_prog =
             "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0";
-        let output = find_maximum_output(&sample_prog)?;
+        let output = find_maximum_output(sample_prog, &[0, 1, 2, 3, 4])?;
         assert_eq!(output, 54321);
 
         Ok(())
@@ -236,7 +304,7 @@ mod tests {
     #[test]
     fn test_find_maximum_output3() -> FaultResult {
         let sample_prog = "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0";
-        let output = find_maximum_output(&sample_prog)?;
+        let output = find_maximum_output(sample_prog, &[0, 1, 2, 3, 4])?;
         assert_eq!(output, 65210);
 
         Ok(())
@@ -0,0 +1,29 @@
+//! Compares decoding a large transmission character by character against working directly on the
+//! raw bytes. Run with `cargo bench`.
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use day_08::{bytes_to_pixels, str_to_pixels};
+
+/// Roughly the size of a real 25x6 transmission with 100 layers, made up of a repeating pattern of
+/// every valid value.
+fn large_transmission() -> String {
+    "012210".repeat(2_500)
+}
+
+fn bench_transmission_parsing(c: &mut Criterion) {
+    let transmission = large_transmission();
+
+    let mut group = c.benchmark_group("transmission_parsing");
+    group.throughput(Throughput::Bytes(transmission.len() as u64));
+
+    group.bench_function("str_to_pixels", |b| b.iter(|| str_to_pixels(&transmission)));
+    group.bench_function("bytes_to_pixels", |b| {
+        b.iter(|| bytes_to_pixels(transmission.as_bytes()).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_transmission_parsing);
+criterion_main!(benches);
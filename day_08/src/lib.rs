@@ -0,0 +1,1028 @@
+use std::fs::File;
+use std::io::{self, BufWriter};
+
+/// The shade transparent pixels are given when the image is written out somewhere that has no
+/// notion of transparency. The message is drawn in white so this keeps it on a black background.
+pub const BACKGROUND_SHADE: u8 = 0x00;
+
+/// Anything that can be stacked up in layers and composited together. The only thing compositing
+/// needs to know about a cell is whether the cells in the layers below it should show through.
+pub trait Cell: Clone + PartialEq {
+    fn is_transparent(&self) -> bool;
+}
+
+/// The transmissions from the puzzle are images made up of pixels, this was originally the only
+/// kind of layered data and is still the most common.
+pub type Image = LayeredImage<Pixel>;
+pub type Layer = ImageLayer<Pixel>;
+
+/// Images using more colors than the puzzle's three are kept as their raw values instead.
+pub type ValueImage = LayeredImage<Value>;
+
+#[derive(Debug, PartialEq)]
+pub struct LayeredImage<C: Cell> {
+    height: usize,
+    width: usize,
+
+    layers: Vec<ImageLayer<C>>,
+}
+
+impl<C: Cell> LayeredImage<C> {
+    /// Extracts a single column of the composited image, top to bottom. Returns None if the column
+    /// is outside of the image.
+    pub fn column(&self, x: usize) -> Option<Vec<C>> {
+        if x >= self.width {
+            return None;
+        }
+
+        let flattened = self.flatten();
+        Some(
+            flattened
+                .pixels
+                .into_iter()
+                .skip(x)
+                .step_by(self.width)
+                .collect(),
+        )
+    }
+
+    /// Stacks all of the layers on top of each other, the first layer being the top most, and
+    /// returns a single layer containing the cells that would be visible through any transparent
+    /// sections above them. Cells that are transparent all the way down stay transparent.
+    pub fn flatten(&self) -> ImageLayer<C> {
+        // Parsing guarantees there is always at least one layer
+        let mut image_output = self.layers[0].pixels.clone();
+
+        for layer in &self.layers[1..] {
+            for (pixel_idx, pixel) in layer.pixels.iter().enumerate() {
+                if pixel.is_transparent() {
+                    continue;
+                }
+
+                if image_output[pixel_idx].is_transparent() {
+                    image_output[pixel_idx] = pixel.clone();
+                }
+            }
+        }
+
+        ImageLayer::new(image_output)
+    }
+
+    /// The composited image broken up into its rows from top to bottom. Every way of outputting
+    /// the image starts from here so they all agree on what is visible.
+    fn composited_rows(&self) -> Vec<Vec<C>> {
+        self.flatten()
+            .pixels
+            .chunks(self.width)
+            .map(|row| row.to_vec())
+            .collect()
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Renders the composited image as text, looking up the character to display for each cell
+    /// with the provided palette. Rows are separated by newlines.
+    pub fn render_with(&self, palette: impl Fn(&C) -> char) -> String {
+        let mut output: String = String::new();
+
+        for row_dat in self.composited_rows() {
+            let row: String = row_dat.iter().map(&palette).collect();
+            output.push_str(&row);
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Produces an iterator over every cell of the composited image in row-major order, yielding
+    /// the `(x, y)` coordinates of the cell along with the cell visible at that location.
+    pub fn iter_composited(&self) -> impl Iterator<Item = (usize, usize, C)> {
+        let width = self.width;
+
+        self.flatten()
+            .pixels
+            .into_iter()
+            .enumerate()
+            .map(move |(idx, pixel)| (idx % width, idx / width, pixel))
+    }
+
+    /// Finds the index of the layer containing the fewest of the target pixel. When multiple
+    /// layers are tied for the fewest the earliest of them is selected, so the result is always
+    /// deterministic for a given image.
+    pub fn layer_with_fewest(&self, target: &C) -> usize {
+        // Note: If this was production code I would need to check that layers has > 0 elements and
+        // return a Result instead, but that isn't a case I need to worry about here...
+        let mut counts = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (i, l.value_count(target)));
+        let (mut min_layer_idx, mut min_layer_count) = counts.next().unwrap();
+
+        for (layer_idx, count) in counts {
+            // This comparison needs to remain strict for the earliest layer to win ties
+            if min_layer_count > count {
+                min_layer_idx = layer_idx;
+                min_layer_count = count;
+            }
+        }
+
+        min_layer_idx
+    }
+
+    /// Builds a new image of the same dimensions by applying the transformation to every cell of
+    /// every layer. The original image is left untouched.
+    pub fn map_pixels(&self, f: impl Fn(&C) -> C) -> Self {
+        Self {
+            height: self.height,
+            width: self.width,
+            layers: self.layers.iter().map(|l| l.map_pixels(&f)).collect(),
+        }
+    }
+
+    /// Builds a new image with all of the layers of `top` stacked above the layers of this one, so
+    /// anything opaque in `top` hides whatever is beneath it once composited. Both images need to
+    /// have the same dimensions.
+    pub fn overlay(&self, top: &Self) -> Result<Self, String> {
+        if self.width != top.width || self.height != top.height {
+            return Err(format!(
+                "Can't overlay a {}x{} image onto a {}x{} one",
+                top.width, top.height, self.width, self.height
+            ));
+        }
+
+        let layers = top
+            .layers
+            .iter()
+            .chain(self.layers.iter())
+            .map(|layer| ImageLayer::new(layer.pixels.clone()))
+            .collect();
+
+        Ok(Self {
+            height: self.height,
+            width: self.width,
+            layers,
+        })
+    }
+
+    pub fn parse(width: usize, height: usize, raw_data: &[C]) -> Result<Self, &'static str> {
+        let mut layers = Vec::new();
+        let mut data = raw_data;
+
+        let layer_size = width * height;
+        if layer_size == 0 {
+            return Err("Both height and width need to sizes greater than zero");
+        }
+
+        if raw_data.is_empty() {
+            return Err("Provided data can't be zero length");
+        }
+
+        if !raw_data.len().is_multiple_of(layer_size) {
+            return Err("Input data could not be broken up into a normal number of layers");
+        }
+
+        loop {
+            let (layer_dat, remaining_data) = data.split_at(layer_size);
+            layers.push(ImageLayer::new(layer_dat.to_vec()));
+            data = remaining_data;
+
+            if data.is_empty() {
+                break;
+            }
+        }
+
+        Ok(Self {
+            height,
+            width,
+            layers,
+        })
+    }
+
+    /// Extracts a single row of the composited image, left to right. Returns None if the row is
+    /// outside of the image.
+    pub fn row(&self, y: usize) -> Option<Vec<C>> {
+        if y >= self.height {
+            return None;
+        }
+
+        let flattened = self.flatten();
+        Some(flattened.pixels[(y * self.width)..((y + 1) * self.width)].to_vec())
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+}
+
+impl Image {
+    pub fn checksum(&self) -> usize {
+        let min_layer_idx = self.layer_with_fewest(&Pixel::Black);
+
+        // Return the product of the count of 1s and 2s on the layer with the fewest zeros per the
+        // spec defined in the problem
+        self.layers[min_layer_idx].value_count(&Pixel::White)
+            * self.layers[min_layer_idx].value_count(&Pixel::Transparent)
+    }
+
+    /// Decodes a raw transmission straight into an image. Unlike going through `str_to_pixels`
+    /// first, a corrupted transmission is reported as an error naming the first bad character
+    /// rather than panicking.
+    pub fn from_transmission(width: usize, height: usize, input: &str) -> Result<Self, String> {
+        let pixels = try_str_to_pixels(input)?;
+        Ok(Self::parse(width, height, &pixels)?)
+    }
+
+    /// Counts each type of pixel on every layer, returning a `(black, white, transparent)` tuple
+    /// for each of them in the same order as the layers. This is the per layer version of
+    /// `total_histogram()`.
+    pub fn layer_stats(&self) -> Vec<(usize, usize, usize)> {
+        self.layers
+            .iter()
+            .map(|layer| {
+                (
+                    layer.value_count(&Pixel::Black),
+                    layer.value_count(&Pixel::White),
+                    layer.value_count(&Pixel::Transparent),
+                )
+            })
+            .collect()
+    }
+
+    /// Finds the layer that hides the most of what is beneath it, that being the one with the
+    /// fewest transparent pixels. Ties go to the earliest layer the same as `layer_with_fewest()`.
+    pub fn most_opaque_layer(&self) -> usize {
+        self.layer_with_fewest(&Pixel::Transparent)
+    }
+
+    pub fn render(&self) -> String {
+        self.render_with(Pixel::to_char)
+    }
+
+    /// Renders the composited image for a terminal that supports true color escape sequences, with
+    /// every pixel becoming a space with its color as the background. Transparent pixels are left
+    /// with the terminal's own background. Colors are reset at the end of every line so nothing
+    /// bleeds into whatever is printed after the image.
+    pub fn render_ansi(&self) -> String {
+        let mut output: String = String::new();
+
+        for row_dat in self.composited_rows() {
+            for pixel in row_dat {
+                match pixel {
+                    Pixel::Transparent => output.push_str("\x1b[49m "),
+                    visible => {
+                        let shade = visible.to_shade();
+                        output.push_str(&format!("\x1b[48;2;{};{};{}m ", shade, shade, shade));
+                    }
+                }
+            }
+
+            output.push_str("\x1b[0m\n");
+        }
+
+        output
+    }
+
+    /// The same as `render()` but with every pixel blown up into a `factor` by `factor` block of
+    /// characters, which makes the message a lot easier to read in a terminal.
+    pub fn render_scaled(&self, factor: usize) -> Result<String, &'static str> {
+        if factor == 0 {
+            return Err("Images can't be scaled down to nothing");
+        }
+
+        let mut output: String = String::new();
+
+        for row_dat in self.composited_rows() {
+            let row: String = row_dat
+                .iter()
+                .flat_map(|c| std::iter::repeat_n(c.to_char(), factor))
+                .collect();
+
+            for _ in 0..factor {
+                output.push_str(&row);
+                output.push('\n');
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// A compact fingerprint of the composited image, useful for checking a decoded image matches
+    /// a known good one without having to embed the rendered output. This uses 64-bit FNV-1a over
+    /// the transmission digits of the visible pixels rather than the standard library's hasher as
+    /// that one isn't guaranteed to be stable between releases.
+    pub fn render_digest(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.flatten()
+            .pixels
+            .iter()
+            .fold(FNV_OFFSET_BASIS, |hash, pixel| {
+                (hash ^ pixel.to_digit() as u64).wrapping_mul(FNV_PRIME)
+            })
+    }
+
+    /// Writes the composited image out as a grayscale PNG with one pixel per image pixel. Black and
+    /// white pixels come out as exactly that, while anything left transparent is filled in with
+    /// the `BACKGROUND_SHADE`.
+    pub fn write_png(&self, path: &str) -> io::Result<()> {
+        let shades: Vec<u8> = self
+            .composited_rows()
+            .iter()
+            .flat_map(|row| row.iter().map(|p| p.to_shade()))
+            .collect();
+
+        let mut encoder = png::Encoder::new(
+            BufWriter::new(File::create(path)?),
+            self.width as u32,
+            self.height as u32,
+        );
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&shades)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    /// Serializes every layer of the image back into the raw transmission format it was originally
+    /// received in. Parsing the result with the same dimensions will produce an identical image.
+    pub fn to_transmission(&self) -> String {
+        self.layers
+            .iter()
+            .flat_map(|layer| layer.pixels.iter())
+            .map(|pixel| pixel.to_digit())
+            .collect()
+    }
+
+    /// Counts each type of pixel across every layer of the image, returned in the order black,
+    /// white, transparent. As every pixel in a valid transmission is one of these three values the
+    /// total of all three will always be equal to `width * height * number_of_layers`, anything
+    /// else indicates the transmission was corrupted.
+    pub fn total_histogram(&self) -> [usize; 3] {
+        let mut histogram = [0; 3];
+
+        for layer in &self.layers {
+            histogram[0] += layer.value_count(&Pixel::Black);
+            histogram[1] += layer.value_count(&Pixel::White);
+            histogram[2] += layer.value_count(&Pixel::Transparent);
+        }
+
+        histogram
+    }
+}
+
+impl ValueImage {
+    /// Builds an image out of raw values such as those from `str_to_values()`. Which of the values
+    /// should be treated as transparent differs from one transmission to the next so it needs to
+    /// be provided.
+    pub fn from_values(
+        width: usize,
+        height: usize,
+        values: &[u8],
+        transparent: u8,
+    ) -> Result<Self, &'static str> {
+        let cells: Vec<Value> = values
+            .iter()
+            .map(|val| Value::new(*val, transparent))
+            .collect();
+
+        Self::parse(width, height, &cells)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImageLayer<C: Cell> {
+    // NOTE: I may want to make this a boxed slice as well...
+    pub pixels: Vec<C>,
+}
+
+impl<C: Cell> ImageLayer<C> {
+    /// Builds a new layer by applying the transformation to each of the cells in this one.
+    pub fn map_pixels(&self, f: impl Fn(&C) -> C) -> Self {
+        Self::new(self.pixels.iter().map(f).collect())
+    }
+
+    pub fn new(pixels: Vec<C>) -> Self {
+        Self { pixels }
+    }
+
+    pub fn value_count(&self, value: &C) -> usize {
+        let mut total = 0;
+
+        for p in &self.pixels {
+            if p == value {
+                total += 1;
+            }
+        }
+
+        total
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Pixel {
+    Black,
+    White,
+    Transparent,
+}
+
+impl Cell for Pixel {
+    fn is_transparent(&self) -> bool {
+        self == &Pixel::Transparent
+    }
+}
+
+impl Pixel {
+    /// The same as `from_char` but working on the raw byte of an ASCII digit, which avoids having
+    /// to decode the transmission as UTF-8 first.
+    pub fn from_byte(val: u8) -> Result<Self, &'static str> {
+        match val {
+            b'0' => Ok(Self::Black),
+            b'1' => Ok(Self::White),
+            b'2' => Ok(Self::Transparent),
+            _ => Err("invalid value attempted to become a pixel"),
+        }
+    }
+
+    pub fn from_char(val: &char) -> Result<Self, &str> {
+        match val {
+            '0' => Ok(Self::Black),
+            '1' => Ok(Self::White),
+            '2' => Ok(Self::Transparent),
+            _ => Err("invalid value attempted to become a pixel"),
+        }
+    }
+
+    /// The reverse of the `from_char` operation, producing the digit used to represent this pixel
+    /// in the raw transmission.
+    pub fn to_digit(&self) -> char {
+        match self {
+            Self::Black => '0',
+            Self::White => '1',
+            Self::Transparent => '2',
+        }
+    }
+
+    /// The grayscale value used for this pixel when writing out an actual image file.
+    pub fn to_shade(&self) -> u8 {
+        match self {
+            Self::Black => 0x00,
+            Self::White => 0xff,
+            Self::Transparent => BACKGROUND_SHADE,
+        }
+    }
+
+    /// This is not a reverse of the `from_char` operation. This results in a character appropriate
+    /// for display the resulting image.
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Black => '█',
+            // Probably could combine these two but ehhh nice to see the differences
+            Self::White => '_',
+            Self::Transparent => ' ',
+        }
+    }
+}
+
+/// A single cell of an image with an arbitrary number of colors. Each cell carries the value that
+/// is considered transparent along with its own so it can be composited without any outside
+/// information.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Value {
+    pub value: u8,
+    pub transparent: u8,
+}
+
+impl Cell for Value {
+    fn is_transparent(&self) -> bool {
+        self.value == self.transparent
+    }
+}
+
+impl Value {
+    pub fn new(value: u8, transparent: u8) -> Self {
+        Self { value, transparent }
+    }
+}
+
+/// A faster alternative to `str_to_pixels` for large transmissions that works directly on the raw
+/// bytes. Surrounding whitespace is ignored the same way, but any other invalid value is reported
+/// as an error along with its position instead of panicking.
+pub fn bytes_to_pixels(input: &[u8]) -> Result<Vec<Pixel>, String> {
+    let start = input
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(input.len());
+    let end = input
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |pos| pos + 1);
+
+    // Collecting into a Result loses the size hint, so allocating up front is noticeably faster
+    let mut pixels = Vec::with_capacity(end - start);
+
+    for (idx, b) in input[start..end].iter().enumerate() {
+        match Pixel::from_byte(*b) {
+            Ok(pixel) => pixels.push(pixel),
+            Err(err) => return Err(format!("{} (byte {} at {})", err, b, start + idx)),
+        }
+    }
+
+    Ok(pixels)
+}
+
+/// Panics if the input contains anything other than the three pixel values, `try_str_to_pixels`
+/// should be used for input that can't be trusted.
+pub fn str_to_pixels(input: &str) -> Vec<Pixel> {
+    input
+        .trim()
+        .chars()
+        .map(|c| Pixel::from_char(&c).unwrap())
+        .collect()
+}
+
+/// A version of `str_to_pixels` that reports invalid values instead of panicking. Surrounding
+/// whitespace is still ignored, but the index in the error is the position of the offending
+/// character in the original input.
+pub fn try_str_to_pixels(input: &str) -> Result<Vec<Pixel>, String> {
+    let leading = input.chars().take_while(|c| c.is_whitespace()).count();
+
+    input
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| {
+            Pixel::from_char(&c)
+                .map_err(|err| format!("{} ('{}' at index {})", err, c, leading + idx))
+        })
+        .collect()
+}
+
+/// The equivalent of `str_to_pixels` for transmissions that aren't limited to the three pixel
+/// colors. Each digit becomes its numeric value. Anything that isn't a digit is reported along
+/// with its index in the original input, the same as `try_str_to_pixels`.
+pub fn str_to_values(input: &str) -> Result<Vec<u8>, String> {
+    let leading = input.chars().take_while(|c| c.is_whitespace()).count();
+
+    input
+        .trim()
+        .chars()
+        .enumerate()
+        .map(|(idx, c)| match c.to_digit(10) {
+            Some(val) => Ok(val as u8),
+            None => Err(format!(
+                "invalid value attempted to become a cell ('{}' at index {})",
+                c,
+                leading + idx
+            )),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_to_pixels() {
+        let input = "0222112222120000";
+        assert_eq!(
+            bytes_to_pixels(input.as_bytes()).unwrap(),
+            str_to_pixels(input)
+        );
+
+        // Surrounding whitespace, such as the newline ending the input file, is ignored
+        let padded = " 0222112222120000\n";
+        assert_eq!(
+            bytes_to_pixels(padded.as_bytes()).unwrap(),
+            str_to_pixels(padded)
+        );
+        assert_eq!(bytes_to_pixels(b"  \n").unwrap(), vec![]);
+
+        assert!(bytes_to_pixels(b"0123").is_err());
+        assert!(bytes_to_pixels(b"01 2").is_err());
+    }
+
+    #[test]
+    fn test_corrupted_transmissions() {
+        assert_eq!(
+            try_str_to_pixels("0012\n"),
+            Ok(vec![
+                Pixel::Black,
+                Pixel::Black,
+                Pixel::White,
+                Pixel::Transparent,
+            ])
+        );
+
+        let err = try_str_to_pixels("0013").unwrap_err();
+        assert!(err.ends_with("('3' at index 3)"), "{}", err);
+
+        // The index still lines up with the original input when there is leading whitespace
+        let err = try_str_to_pixels("\n 01x2").unwrap_err();
+        assert!(err.ends_with("('x' at index 4)"), "{}", err);
+
+        let err = Image::from_transmission(2, 2, "0013").unwrap_err();
+        assert!(err.ends_with("('3' at index 3)"), "{}", err);
+
+        // Problems with the dimensions still come through as well
+        assert!(Image::from_transmission(3, 3, "0012").is_err());
+        assert_eq!(
+            Image::from_transmission(2, 2, "0222112222120000\n"),
+            Image::parse(2, 2, &str_to_pixels("0222112222120000")).map_err(|err| err.to_string())
+        );
+    }
+
+    #[test]
+    fn test_image_parsing() {
+        // Reject zero in either height or width
+        assert!(Image::parse(0, 100, &[]).is_err());
+        assert!(Image::parse(100, 0, &[]).is_err());
+
+        // Reject incorrect lengths
+        assert!(Image::parse(1, 1, &[]).is_err());
+        assert!(Image::parse(1, 2, &[Pixel::Black]).is_err());
+        assert!(Image::parse(1, 1, &[Pixel::Black]).is_ok());
+    }
+
+    #[test]
+    fn test_modified_official_case() {
+        // The official case is "123456789012" but that contains invalid values once the second
+        // portion is revealed, I've replaced it with a unique non-repeating pattern containing
+        // only valid values
+        let input = "001210222011";
+        let parsed_input = Image::parse(3, 2, &str_to_pixels(input)).unwrap();
+
+        let expected_output = Image {
+            height: 2,
+            width: 3,
+            layers: vec![
+                Layer::new(vec![
+                    Pixel::Black,
+                    Pixel::Black,
+                    Pixel::White,
+                    Pixel::Transparent,
+                    Pixel::White,
+                    Pixel::Black,
+                ]),
+                Layer::new(vec![
+                    Pixel::Transparent,
+                    Pixel::Transparent,
+                    Pixel::Transparent,
+                    Pixel::Black,
+                    Pixel::White,
+                    Pixel::White,
+                ]),
+            ],
+        };
+
+        assert_eq!(parsed_input, expected_output);
+    }
+
+    #[test]
+    fn test_layer_stats() {
+        let image = Image::parse(3, 2, &str_to_pixels("001210222011")).unwrap();
+
+        assert_eq!(image.layer_stats(), vec![(3, 2, 1), (1, 2, 3)]);
+        assert_eq!(image.most_opaque_layer(), 0);
+
+        // The per layer counts always add back up to the totals
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        assert_eq!(
+            image.layer_stats(),
+            vec![(1, 0, 3), (0, 2, 2), (0, 1, 3), (4, 0, 0)]
+        );
+        assert_eq!(image.most_opaque_layer(), 3);
+        assert_eq!(image.total_histogram(), [5, 3, 8]);
+    }
+
+    #[test]
+    fn test_composited_iteration() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        let cells: Vec<(usize, usize, Pixel)> = image.iter_composited().collect();
+
+        assert_eq!(
+            cells,
+            vec![
+                (0, 0, Pixel::Black),
+                (1, 0, Pixel::White),
+                (0, 1, Pixel::White),
+                (1, 1, Pixel::Black),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layer_with_fewest_ties() {
+        // The first two layers both have a single black pixel, the third has two of them
+        let test_image = Image::parse(2, 2, &str_to_pixels("011110120022")).unwrap();
+
+        assert_eq!(test_image.layer_with_fewest(&Pixel::Black), 0);
+        assert_eq!(test_image.layer_with_fewest(&Pixel::White), 2);
+        assert_eq!(test_image.layer_with_fewest(&Pixel::Transparent), 0);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Terrain {
+        Open,
+        Rock,
+        Water,
+    }
+
+    impl Cell for Terrain {
+        fn is_transparent(&self) -> bool {
+            self == &Terrain::Open
+        }
+    }
+
+    #[test]
+    fn test_generic_cell_compositing() {
+        let terrain = vec![
+            // The surface layer, only the water is visible from above
+            Terrain::Open,
+            Terrain::Water,
+            Terrain::Open,
+            Terrain::Open,
+            // The bedrock beneath it, with a single gap in it
+            Terrain::Rock,
+            Terrain::Rock,
+            Terrain::Open,
+            Terrain::Rock,
+        ];
+        let map = LayeredImage::parse(2, 2, &terrain).unwrap();
+
+        assert_eq!(
+            map.flatten(),
+            ImageLayer::new(vec![
+                Terrain::Rock,
+                Terrain::Water,
+                Terrain::Open,
+                Terrain::Rock,
+            ])
+        );
+        assert_eq!(map.row(0), Some(vec![Terrain::Rock, Terrain::Water]));
+        assert_eq!(map.layer_with_fewest(&Terrain::Rock), 0);
+    }
+
+    #[test]
+    fn test_custom_palette() {
+        // Four is the see-through value here, the bottom left position is transparent all the way
+        // down
+        let values = str_to_values("044123444443\n").unwrap();
+        assert_eq!(values, vec![0, 4, 4, 1, 2, 3, 4, 4, 4, 4, 4, 3]);
+
+        let image = ValueImage::from_values(2, 2, &values, 4).unwrap();
+        assert_eq!(
+            image.flatten(),
+            ImageLayer::new(vec![
+                Value::new(0, 4),
+                Value::new(3, 4),
+                Value::new(4, 4),
+                Value::new(1, 4),
+            ])
+        );
+
+        // Stray characters, even whitespace in the middle of the data, are reported
+        let err = str_to_values("0441\n2344").unwrap_err();
+        assert!(err.ends_with("('\n' at index 4)"), "{:?}", err);
+        let err = str_to_values(" 04a1").unwrap_err();
+        assert!(err.ends_with("('a' at index 3)"), "{:?}", err);
+
+        let palette = ['.', '#', '+', '*', ' '];
+        assert_eq!(image.render_with(|v| palette[v.value as usize]), ".*\n #\n");
+
+        // Changing what is transparent changes what shows through
+        let image = ValueImage::from_values(2, 2, &values, 0).unwrap();
+        assert_eq!(image.render_with(|v| palette[v.value as usize]), "+ \n #\n");
+    }
+
+    #[test]
+    fn test_flattening() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        let expected = Layer::new(vec![Pixel::Black, Pixel::White, Pixel::White, Pixel::Black]);
+
+        assert_eq!(image.flatten(), expected);
+        assert_eq!(image.render(), "█_\n_█\n");
+    }
+
+    /// Reads back a grayscale PNG written by `write_png()`, returning its dimensions along with
+    /// the shade of every pixel in row-major order.
+    fn read_png(path: &std::path::Path) -> (u32, u32, Vec<u8>) {
+        let decoder = png::Decoder::new(File::open(path).unwrap());
+        let mut reader = decoder.read_info().unwrap();
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).unwrap();
+        assert_eq!(info.color_type, png::ColorType::Grayscale);
+        assert_eq!(info.bit_depth, png::BitDepth::Eight);
+
+        buffer.truncate(info.buffer_size());
+        (info.width, info.height, buffer)
+    }
+
+    #[test]
+    fn test_write_png() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+
+        let path = std::env::temp_dir().join(format!("day_08_{}.png", std::process::id()));
+        image.write_png(path.to_str().unwrap()).unwrap();
+        let written = read_png(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(written, (2, 2, vec![0x00, 0xff, 0xff, 0x00]));
+
+        // Anything left transparent ends up as the background
+        let see_through = Image::parse(2, 1, &[Pixel::Transparent, Pixel::White]).unwrap();
+        see_through.write_png(path.to_str().unwrap()).unwrap();
+        let written = read_png(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(written, (2, 1, vec![BACKGROUND_SHADE, 0xff]));
+    }
+
+    #[test]
+    fn test_ansi_rendering() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        assert_eq!(
+            image.render_ansi(),
+            "\x1b[48;2;0;0;0m \x1b[48;2;255;255;255m \x1b[0m\n\
+             \x1b[48;2;255;255;255m \x1b[48;2;0;0;0m \x1b[0m\n"
+        );
+
+        let see_through = Image::parse(1, 1, &[Pixel::Transparent]).unwrap();
+        assert_eq!(see_through.render_ansi(), "\x1b[49m \x1b[0m\n");
+
+        // The plain rendering doesn't pick up any of the escape sequences
+        assert!(!image.render().contains('\x1b'));
+    }
+
+    #[test]
+    fn test_scaled_rendering() {
+        let white = Image::parse(1, 1, &[Pixel::White]).unwrap();
+        assert_eq!(white.render_scaled(3), Ok("___\n___\n___\n".to_string()));
+        assert!(white.render_scaled(0).is_err());
+
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        assert_eq!(image.render_scaled(1), Ok(image.render()));
+        assert_eq!(
+            image.render_scaled(2),
+            Ok("██__\n██__\n__██\n__██\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_digest() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        assert_eq!(image.render_digest(), 0xb999_a0f9_e682_5b73);
+
+        // Only the visible pixels matter, not how they ended up visible
+        let flat = Image::parse(2, 2, &str_to_pixels("0110")).unwrap();
+        assert_eq!(flat.render_digest(), image.render_digest());
+
+        let inverted = Image::parse(2, 2, &str_to_pixels("1001")).unwrap();
+        assert_ne!(inverted.render_digest(), image.render_digest());
+    }
+
+    #[test]
+    fn test_transmission_round_trip() {
+        let input = "0222112222120000";
+        let image = Image::parse(2, 2, &str_to_pixels(input)).unwrap();
+
+        let transmission = image.to_transmission();
+        assert_eq!(transmission, input);
+        assert_eq!(
+            Image::parse(2, 2, &str_to_pixels(&transmission)).unwrap(),
+            image
+        );
+    }
+
+    #[test]
+    fn test_mapping_pixels() {
+        let hide_black = |p: &Pixel| match p {
+            Pixel::Black => Pixel::Transparent,
+            other => other.clone(),
+        };
+
+        let layer = Layer::new(vec![Pixel::Black, Pixel::White, Pixel::Transparent]);
+        assert_eq!(
+            layer.map_pixels(hide_black),
+            Layer::new(vec![Pixel::Transparent, Pixel::White, Pixel::Transparent])
+        );
+
+        // With the black pixels hidden the white ones from the lower layers show through
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        let mapped = image.map_pixels(hide_black);
+
+        assert_eq!(mapped.to_transmission(), "2222112222122222");
+        assert_eq!(
+            mapped.flatten(),
+            Layer::new(vec![
+                Pixel::White,
+                Pixel::White,
+                Pixel::White,
+                Pixel::Transparent,
+            ])
+        );
+    }
+
+    #[test]
+    fn test_overlay() {
+        let solid = Image::parse(2, 2, &str_to_pixels("0000")).unwrap();
+        let mostly_clear = Image::parse(2, 2, &str_to_pixels("22122221")).unwrap();
+
+        let combined = solid.overlay(&mostly_clear).unwrap();
+        assert_eq!(combined.layers.len(), 3);
+        assert_eq!(combined.to_transmission(), "221222210000");
+        assert_eq!(combined.render(), "██\n__\n");
+
+        // The other way around the solid layer hides everything
+        assert_eq!(mostly_clear.overlay(&solid).unwrap().render(), "██\n██\n");
+
+        let wide = Image::parse(4, 1, &str_to_pixels("2222")).unwrap();
+        assert!(solid.overlay(&wide).is_err());
+    }
+
+    #[test]
+    fn test_total_histogram() {
+        let image = Image::parse(2, 2, &str_to_pixels("0222112222120000")).unwrap();
+        let histogram = image.total_histogram();
+
+        assert_eq!(histogram, [5, 3, 8]);
+        assert_eq!(
+            histogram.iter().sum::<usize>(),
+            image.width() * image.height() * image.layers.len()
+        );
+    }
+
+    #[test]
+    fn test_row_and_column_extraction() {
+        let image = Image::parse(3, 2, &str_to_pixels("001210222011")).unwrap();
+
+        assert_eq!(
+            image.row(0),
+            Some(vec![Pixel::Black, Pixel::Black, Pixel::White])
+        );
+        assert_eq!(
+            image.row(1),
+            Some(vec![Pixel::Black, Pixel::White, Pixel::Black])
+        );
+        assert_eq!(image.row(2), None);
+
+        assert_eq!(image.column(0), Some(vec![Pixel::Black, Pixel::Black]));
+        assert_eq!(image.column(2), Some(vec![Pixel::White, Pixel::Black]));
+        assert_eq!(image.column(3), None);
+    }
+
+    #[test]
+    fn test_layer_value_counting() {
+        let layer = Layer::new(vec![
+            Pixel::Black,
+            Pixel::White,
+            Pixel::White,
+            Pixel::Black,
+            Pixel::Black,
+            Pixel::Black,
+            Pixel::White,
+        ]);
+
+        assert_eq!(layer.value_count(&Pixel::Black), 4);
+        assert_eq!(layer.value_count(&Pixel::White), 3);
+        assert_eq!(layer.value_count(&Pixel::Transparent), 0);
+    }
+
+    #[test]
+    fn test_checksum() {
+        let test_image = Image {
+            height: 2,
+            width: 3,
+            layers: vec![
+                // This layer should have a checksum of 4
+                Layer::new(vec![
+                    Pixel::Black,
+                    Pixel::Black,
+                    Pixel::White,
+                    Pixel::White,
+                    Pixel::Transparent,
+                    Pixel::Transparent,
+                ]),
+                // This layer should not be selected, but would have a checksum of 2
+                Layer::new(vec![
+                    Pixel::Black,
+                    Pixel::Black,
+                    Pixel::Black,
+                    Pixel::White,
+                    Pixel::White,
+                    Pixel::Transparent,
+                ]),
+            ],
+        };
+
+        assert_eq!(test_image.checksum(), 4);
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+
+use common::{AocError, Point};
+
+/// The bug grid is always a 5x5 square.
+const GRID_SIZE: isize = 5;
+
+/// Converts a map of bugs (`#`) and empty tiles (`.`) into a bitmask layout. Each tile takes the
+/// next bit reading left to right, top to bottom, which conveniently means the layout itself is
+/// also its biodiversity rating. Whitespace is ignored and anything that isn't a bug is treated as
+/// empty.
+pub fn parse_grid(input: &str) -> u32 {
+    input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .take((GRID_SIZE * GRID_SIZE) as usize)
+        .enumerate()
+        .filter(|(_, c)| *c == '#')
+        .fold(0, |layout, (idx, _)| layout | (1 << idx))
+}
+
+fn has_bug(layout: u32, point: &Point) -> bool {
+    if point.x < 0 || point.x >= GRID_SIZE || point.y < 0 || point.y >= GRID_SIZE {
+        return false;
+    }
+
+    layout & (1 << (point.y * GRID_SIZE + point.x)) != 0
+}
+
+/// Advances the layout by one minute. A bug survives only when exactly one of its neighbors has a
+/// bug, and an empty tile becomes infested when one or two of its neighbors do. Every tile is
+/// decided from the previous layout so the whole grid updates at once.
+pub fn step(layout: u32) -> u32 {
+    let mut next_layout = 0;
+
+    for y in 0..GRID_SIZE {
+        for x in 0..GRID_SIZE {
+            let tile = Point::new(x, y);
+            let adjacent_bugs = tile
+                .neighbors4()
+                .iter()
+                .filter(|n| has_bug(layout, n))
+                .count();
+
+            let infested = matches!(
+                (has_bug(layout, &tile), adjacent_bugs),
+                (true, 1) | (false, 1) | (false, 2)
+            );
+
+            if infested {
+                next_layout |= 1 << (y * GRID_SIZE + x);
+            }
+        }
+    }
+
+    next_layout
+}
+
+/// Steps the layout until one is produced that has been seen before and returns the biodiversity
+/// rating of it. There are only a finite number of layouts so this is guaranteed to finish.
+pub fn first_repeat(initial: u32) -> u32 {
+    let mut seen = HashSet::new();
+    let mut layout = initial;
+
+    while seen.insert(layout) {
+        layout = step(layout);
+    }
+
+    layout
+}
+
+fn main() -> Result<(), AocError> {
+    let mut in_dat_fh = File::open("./data/input.txt")?;
+    let mut in_dat = String::new();
+
+    in_dat_fh.read_to_string(&mut in_dat)?;
+
+    let rating = first_repeat(parse_grid(&in_dat));
+    println!(
+        "Biodiversity rating of the first repeated layout: {}",
+        rating
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "....#\n#..#.\n#..##\n..#..\n#....\n";
+
+    #[test]
+    fn test_grid_parsing() {
+        let layout = parse_grid(".....\n.....\n.....\n#....\n.#...\n");
+        assert_eq!(layout, 2_129_920);
+
+        assert_eq!(parse_grid("#....\n.....\n.....\n.....\n....."), 1);
+    }
+
+    #[test]
+    fn test_stepping() {
+        let after_one = parse_grid("#..#.\n####.\n###.#\n##.##\n.##..\n");
+        let after_two = parse_grid("#####\n....#\n....#\n...#.\n#.###\n");
+
+        assert_eq!(step(parse_grid(EXAMPLE)), after_one);
+        assert_eq!(step(after_one), after_two);
+    }
+
+    #[test]
+    fn test_first_repeat() {
+        assert_eq!(first_repeat(parse_grid(EXAMPLE)), 2_129_920);
+    }
+}